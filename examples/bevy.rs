@@ -1,21 +1,13 @@
 use bevy::prelude::*;
 
-use benimator::FrameRate;
-
-// Create the animation component
-// Note: you may make the animation an asset instead of a component
-#[derive(Component, Deref)]
-struct Animation(benimator::Animation);
-
-// Create the player component
-#[derive(Default, Component, Deref, DerefMut)]
-struct AnimationState(benimator::State);
+use benimator::bevy::{BenimatorPlugin, SpriteAnimationState};
 
 fn main() {
     App::new()
         .add_plugins(DefaultPlugins.set(ImagePlugin::default_nearest()))
+        // Add the plugin, to update the animations and the texture atlas indices
+        .add_plugins(BenimatorPlugin)
         .add_systems(Startup, spawn)
-        .add_systems(Update, animate)
         .run();
 }
 
@@ -27,11 +19,9 @@ fn spawn(
     // Don't forget the camera ;-)
     commands.spawn(Camera2dBundle::default());
 
-    // Create an animation
-    let animation = Animation(benimator::Animation::from_indices(
-        0..=4,
-        FrameRate::from_fps(12.0),
-    ));
+    // Load the animation
+    // Note: with bevy's `file_watcher` feature, the animation is hot-reloaded when the file is modified
+    let animation: Handle<benimator::Animation> = asset_server.load("coin.animation.yml");
 
     commands
         // Spawn a bevy sprite-sheet
@@ -50,18 +40,5 @@ fn spawn(
         // Insert the animation
         .insert(animation)
         // Insert the state
-        .insert(AnimationState::default());
-}
-
-fn animate(
-    time: Res<Time>,
-    mut query: Query<(&mut AnimationState, &mut TextureAtlasSprite, &Animation)>,
-) {
-    for (mut player, mut texture, animation) in query.iter_mut() {
-        // Update the state
-        player.update(animation, time.delta());
-
-        // Update the texture atlas
-        texture.index = player.frame_index();
-    }
+        .insert(SpriteAnimationState::default());
 }
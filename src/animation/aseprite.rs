@@ -0,0 +1,339 @@
+use std::{
+    error::Error as StdError,
+    fmt::{self, Display, Formatter},
+    num::NonZeroU32,
+    time::Duration,
+};
+
+use serde::{
+    de::{IgnoredAny, MapAccess, SeqAccess, Visitor},
+    Deserialize, Deserializer,
+};
+
+use super::{Animation, AnimationSet, Frame};
+
+impl AnimationSet {
+    /// Import the animations of a sprite-sheet exported by [Aseprite](https://www.aseprite.org)
+    ///
+    /// Both the "hash" and "array" variants of the JSON data are supported.
+    /// The index of each frame is its position in the exported `frames`,
+    /// and every tag of `meta.frameTags` becomes an animation named after the tag.
+    ///
+    /// The directions are mapped as follows:
+    /// * `forward`: the frames of the tag, [repeated](Animation::repeat)
+    /// * `reverse`: the frames of the tag in reverse order, repeated
+    /// * `pingpong`: the frames of the tag, played in [ping-pong](Animation::ping_pong)
+    /// * `pingpong_reverse`: the frames of the tag in reverse order, played in ping-pong
+    ///
+    /// When the tag has a `repeat` count, the animation is played that number of times
+    /// (see [`repeat_times`](Animation::repeat_times) and [`ping_pong_times`](Animation::ping_pong_times)).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use benimator::*;
+    ///
+    /// let json = r#"{
+    ///   "frames": [
+    ///     { "filename": "hero 0.aseprite", "duration": 100 },
+    ///     { "filename": "hero 1.aseprite", "duration": 150 },
+    ///     { "filename": "hero 2.aseprite", "duration": 100 }
+    ///   ],
+    ///   "meta": {
+    ///     "frameTags": [
+    ///       { "name": "walk", "from": 0, "to": 2, "direction": "pingpong" }
+    ///     ]
+    ///   }
+    /// }"#;
+    ///
+    /// let set = AnimationSet::from_aseprite_json(json).unwrap();
+    /// assert!(set.get("walk").is_ok());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`AsepriteError`] if the JSON is invalid, if a frame has a zero duration,
+    /// if a tag refers to frames that do not exist, or if two tags have the same name.
+    pub fn from_aseprite_json(json: impl AsRef<[u8]>) -> Result<Self, AsepriteError> {
+        let sheet: SpriteSheet = serde_json::from_slice(json.as_ref())
+            .map_err(|error| AsepriteErrorKind::Json(error.to_string()))?;
+        let frames = sheet
+            .frames
+            .0
+            .iter()
+            .enumerate()
+            .map(|(index, frame)| match frame.duration {
+                0 => Err(AsepriteErrorKind::ZeroDuration { frame: index }),
+                duration => Ok(Frame::new(index, Duration::from_millis(duration))),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut set = Self::new();
+        for tag in sheet.meta.frame_tags {
+            if tag.from > tag.to || tag.to >= frames.len() {
+                return Err(AsepriteErrorKind::InvalidRange { tag: tag.name }.into());
+            }
+            if set.animations.contains_key(&tag.name) {
+                return Err(AsepriteErrorKind::DuplicateTag { tag: tag.name }.into());
+            }
+            let tag_frames = frames[tag.from..=tag.to].iter().copied();
+            let (animation, ping_pong) = match tag.direction {
+                Direction::Forward => (Animation::from_frames(tag_frames), false),
+                Direction::Reverse => (Animation::from_frames(tag_frames.rev()), false),
+                Direction::PingPong => (Animation::from_frames(tag_frames), true),
+                Direction::PingPongReverse => (Animation::from_frames(tag_frames.rev()), true),
+            };
+            let animation = match (tag.repeat.0, ping_pong) {
+                (None, false) => animation.repeat(),
+                (None, true) => animation.ping_pong(),
+                (Some(times), false) => animation.repeat_times(times),
+                (Some(times), true) => animation.ping_pong_times(times),
+            };
+            set.animations.insert(tag.name, animation);
+        }
+        Ok(set)
+    }
+}
+
+/// Reason why an imported Aseprite sprite-sheet is invalid
+///
+/// See [`AnimationSet::from_aseprite_json`]
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct AsepriteError(AsepriteErrorKind);
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+enum AsepriteErrorKind {
+    Json(String),
+    ZeroDuration { frame: usize },
+    InvalidRange { tag: String },
+    DuplicateTag { tag: String },
+}
+
+impl From<AsepriteErrorKind> for AsepriteError {
+    fn from(kind: AsepriteErrorKind) -> Self {
+        AsepriteError(kind)
+    }
+}
+
+impl Display for AsepriteError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid aseprite sprite-sheet: ")?;
+        match &self.0 {
+            AsepriteErrorKind::Json(error) => write!(f, "invalid json: {error}"),
+            AsepriteErrorKind::ZeroDuration { frame } => {
+                write!(f, "frame {frame} has a zero duration")
+            }
+            AsepriteErrorKind::InvalidRange { tag } => {
+                write!(f, "tag {tag:?} refers to frames that do not exist")
+            }
+            AsepriteErrorKind::DuplicateTag { tag } => write!(f, "duplicate tag {tag:?}"),
+        }
+    }
+}
+
+impl StdError for AsepriteError {}
+
+#[derive(Deserialize)]
+struct SpriteSheet {
+    frames: Frames,
+    meta: Meta,
+}
+
+#[derive(Deserialize)]
+struct FrameData {
+    duration: u64,
+}
+
+/// Frames of the sprite-sheet, in the "array" or "hash" format
+///
+/// The keys of the hash format are ignored, but the order of the entries is preserved.
+struct Frames(Vec<FrameData>);
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Meta {
+    #[serde(default)]
+    frame_tags: Vec<Tag>,
+}
+
+#[derive(Deserialize)]
+struct Tag {
+    name: String,
+    from: usize,
+    to: usize,
+    #[serde(default)]
+    direction: Direction,
+    #[serde(default)]
+    repeat: Repeat,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+enum Direction {
+    #[default]
+    Forward,
+    Reverse,
+    #[serde(rename = "pingpong")]
+    PingPong,
+    #[serde(rename = "pingpong_reverse")]
+    PingPongReverse,
+}
+
+/// Number of times a tag is played, exported as a string by Aseprite (`None` if forever)
+#[derive(Default)]
+struct Repeat(Option<NonZeroU32>);
+
+impl<'de> Deserialize<'de> for Frames {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct FramesVisitor;
+
+        impl<'de> Visitor<'de> for FramesVisitor {
+            type Value = Frames;
+
+            fn expecting(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+                formatter.write_str("an array or a map of frames")
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+                let mut frames = Vec::with_capacity(seq.size_hint().unwrap_or_default());
+                while let Some(frame) = seq.next_element()? {
+                    frames.push(frame);
+                }
+                Ok(Frames(frames))
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut frames = Vec::with_capacity(map.size_hint().unwrap_or_default());
+                while let Some((IgnoredAny, frame)) = map.next_entry()? {
+                    frames.push(frame);
+                }
+                Ok(Frames(frames))
+            }
+        }
+
+        deserializer.deserialize_any(FramesVisitor)
+    }
+}
+
+impl<'de> Deserialize<'de> for Repeat {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum RepeatDto {
+            Number(u32),
+            String(String),
+        }
+
+        let times = match RepeatDto::deserialize(deserializer)? {
+            RepeatDto::Number(times) => times,
+            RepeatDto::String(times) if times.is_empty() => 0,
+            RepeatDto::String(times) => times.parse().map_err(serde::de::Error::custom)?,
+        };
+        Ok(Self(NonZeroU32::new(times)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::animation::Mode;
+
+    const HASH: &str = r##"{
+      "frames": {
+        "hero d": { "frame": { "x": 0, "y": 0, "w": 16, "h": 16 }, "duration": 100 },
+        "hero c": { "frame": { "x": 16, "y": 0, "w": 16, "h": 16 }, "duration": 200 },
+        "hero b": { "frame": { "x": 32, "y": 0, "w": 16, "h": 16 }, "duration": 300 },
+        "hero a": { "frame": { "x": 48, "y": 0, "w": 16, "h": 16 }, "duration": 400 }
+      },
+      "meta": {
+        "app": "https://www.aseprite.org/",
+        "frameTags": [
+          { "name": "forward", "from": 0, "to": 2, "direction": "forward", "color": "#000000ff" },
+          { "name": "reverse", "from": 1, "to": 3, "direction": "reverse", "repeat": "2" },
+          { "name": "pingpong", "from": 0, "to": 3, "direction": "pingpong" },
+          { "name": "pingpong_reverse", "from": 0, "to": 3, "direction": "pingpong_reverse", "repeat": "3" }
+        ]
+      }
+    }"##;
+
+    fn frames(indices: &[usize]) -> Vec<Frame> {
+        indices
+            .iter()
+            .map(|&i| Frame::new(i, Duration::from_millis(100 * (i as u64 + 1))))
+            .collect()
+    }
+
+    #[test]
+    fn imports_hash() {
+        let set = AnimationSet::from_aseprite_json(HASH).unwrap();
+        assert_eq!(
+            set.get("forward"),
+            Ok(&Animation::from_frames(frames(&[0, 1, 2])).repeat())
+        );
+        assert_eq!(
+            set.get("reverse"),
+            Ok(&Animation::from_frames(frames(&[3, 2, 1]))
+                .repeat_times(NonZeroU32::new(2).unwrap()))
+        );
+        assert_eq!(
+            set.get("pingpong"),
+            Ok(&Animation::from_frames(frames(&[0, 1, 2, 3])).ping_pong())
+        );
+        assert_eq!(
+            set.get("pingpong_reverse").unwrap().mode,
+            Mode::PingPongTimes(NonZeroU32::new(3).unwrap())
+        );
+        assert_eq!(
+            set.get("pingpong_reverse").unwrap().frames,
+            frames(&[3, 2, 1, 0])
+        );
+    }
+
+    #[test]
+    fn imports_array() {
+        let json = r#"{
+          "frames": [
+            { "filename": "b", "duration": 100 },
+            { "filename": "a", "duration": 200 }
+          ],
+          "meta": { "frameTags": [{ "name": "idle", "from": 0, "to": 1, "direction": "forward", "repeat": 0 }] }
+        }"#;
+        let set = AnimationSet::from_aseprite_json(json).unwrap();
+        assert_eq!(
+            set.get("idle"),
+            Ok(&Animation::from_frames(frames(&[0, 1])).repeat())
+        );
+    }
+
+    #[test]
+    fn no_tags() {
+        let json = r#"{ "frames": [{ "duration": 100 }], "meta": {} }"#;
+        assert!(AnimationSet::from_aseprite_json(json).unwrap().is_empty());
+    }
+
+    #[rstest]
+    #[case::invalid_json("{")]
+    #[case::zero_duration(r#"{ "frames": [{ "duration": 0 }], "meta": {} }"#)]
+    #[case::out_of_range(
+        r#"{ "frames": [{ "duration": 100 }], "meta": { "frameTags": [{ "name": "a", "from": 0, "to": 1 }] } }"#
+    )]
+    #[case::duplicate_tag(
+        r#"{ "frames": [{ "duration": 100 }], "meta": { "frameTags": [
+            { "name": "a", "from": 0, "to": 0 },
+            { "name": "a", "from": 0, "to": 0 }
+        ] } }"#
+    )]
+    fn invalid(#[case] json: &str) {
+        assert!(AnimationSet::from_aseprite_json(json).is_err());
+    }
+
+    #[test]
+    fn error_message_names_the_problem() {
+        let json = r#"{ "frames": [{ "duration": 0 }], "meta": {} }"#;
+        assert_eq!(
+            AnimationSet::from_aseprite_json(json)
+                .unwrap_err()
+                .to_string(),
+            "invalid aseprite sprite-sheet: frame 0 has a zero duration"
+        );
+    }
+}
@@ -0,0 +1,195 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Value of a frame which sprite is in one of several texture atlases
+///
+/// It is meant for animations that don't fit in a single sprite sheet.
+/// The renderer can then swap the texture when the atlas of the current frame changes.
+///
+/// [`State::frame_index`](crate::State::frame_index) and [`Event::FrameEntered`](crate::Event::FrameEntered)
+/// report the index in the atlas, and the atlas is given by the [value](crate::State::frame_value) of the frame.
+///
+/// # Example
+///
+/// ```
+/// # use std::time::Duration;
+/// use benimator::*;
+///
+/// let animation = Animation::from_values(
+///     [
+///         AtlasIndex::new("boss_a", 14),
+///         AtlasIndex::new("boss_a", 15),
+///         AtlasIndex::new("boss_b", 0),
+///     ],
+///     FrameRate::from_fps(10.0),
+/// );
+/// let mut state = State::new();
+///
+/// let current = state.update(&animation, Duration::from_millis(250)).value();
+/// assert_eq!(current.atlas(), "boss_b");
+/// assert_eq!(current.index(), 0);
+/// assert_eq!(state.frame_index(), 0);
+/// ```
+#[cfg_attr(
+    feature = "serde",
+    doc = "
+
+# Deserialization format
+
+The frames are maps of the atlas and index, which may define the other properties of the frame as well:
+
+```yaml
+fps: 12
+frames:
+  - { atlas: boss_a, index: 14 }
+  - { atlas: boss_a, index: 15, duration: 200 }
+  - { atlas: boss_b, index: 0 }
+```
+"
+)]
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AtlasIndex {
+    atlas: String,
+    index: usize,
+}
+
+impl AtlasIndex {
+    /// Create a reference to the sprite at `index` in the atlas identified by `atlas`
+    #[must_use]
+    pub fn new(atlas: impl Into<String>, index: usize) -> Self {
+        Self {
+            atlas: atlas.into(),
+            index,
+        }
+    }
+
+    /// Returns the identifier of the atlas
+    #[must_use]
+    pub fn atlas(&self) -> &str {
+        &self.atlas
+    }
+
+    /// Returns the index of the sprite in the atlas
+    #[must_use]
+    pub fn index(&self) -> usize {
+        self.index
+    }
+}
+
+/// Value of a frame that may refer to a sprite in a texture atlas
+///
+/// The frames of an animation are played by a [`State`](crate::State) if their value implements it,
+/// so that [`State::frame_index`](crate::State::frame_index), [`Event::FrameEntered`](crate::Event::FrameEntered)
+/// and [`Sample::index`](crate::Sample::index) report the index of the sprite.
+///
+/// It is implemented for `usize` (the index in the sprite atlas) and [`AtlasIndex`],
+/// and for `char`, `&str` and `String`, which have no sprite index.
+///
+/// # Example
+///
+/// ```
+/// use benimator::*;
+///
+/// struct Sprite {
+///     index: usize,
+///     visible: bool,
+/// }
+///
+/// impl SpriteIndex for Sprite {
+///     fn sprite_index(&self) -> Option<usize> {
+///         self.visible.then_some(self.index)
+///     }
+/// }
+/// ```
+pub trait SpriteIndex {
+    /// Returns the index of the sprite in the texture atlas, or `None` if the value does not refer to a sprite
+    fn sprite_index(&self) -> Option<usize>;
+
+    /// Returns the identifier of the texture atlas, if the value refers to a sprite in one of several atlases
+    ///
+    /// The sprite index of such a value is not checked against [`Validation::with_atlas_size`](crate::Validation::with_atlas_size),
+    /// since the atlases may have different sizes.
+    fn atlas(&self) -> Option<&str> {
+        None
+    }
+}
+
+impl SpriteIndex for usize {
+    fn sprite_index(&self) -> Option<usize> {
+        Some(*self)
+    }
+}
+
+impl SpriteIndex for AtlasIndex {
+    fn sprite_index(&self) -> Option<usize> {
+        Some(self.index)
+    }
+
+    fn atlas(&self) -> Option<&str> {
+        Some(&self.atlas)
+    }
+}
+
+impl SpriteIndex for char {
+    fn sprite_index(&self) -> Option<usize> {
+        None
+    }
+}
+
+impl SpriteIndex for &str {
+    fn sprite_index(&self) -> Option<usize> {
+        None
+    }
+}
+
+impl SpriteIndex for String {
+    fn sprite_index(&self) -> Option<usize> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::time::Duration;
+
+    use super::*;
+    use crate::{Animation, Event, FrameRate, State, Validation};
+
+    fn animation() -> Animation<AtlasIndex> {
+        Animation::from_values(
+            [AtlasIndex::new("a", 7), AtlasIndex::new("b", 2)],
+            FrameRate::from_frame_duration(Duration::from_secs(1)),
+        )
+    }
+
+    #[test]
+    fn state_reports_the_index_in_the_atlas() {
+        let animation = animation();
+        let mut state = State::new();
+        let mut events = Vec::new();
+        state.update_with_events(&animation, Duration::from_millis(1500), &mut events);
+        assert_eq!(state.frame_index(), 2);
+        assert_eq!(state.frame_value(&animation), &AtlasIndex::new("b", 2));
+        assert_eq!(
+            events,
+            vec![
+                Event::FrameEntered {
+                    position: 0,
+                    index: Some(7)
+                },
+                Event::FrameEntered {
+                    position: 1,
+                    index: Some(2)
+                },
+            ]
+        );
+        assert_eq!(animation.frame_at(Duration::ZERO).index(), Some(7));
+    }
+
+    #[test]
+    fn atlas_size_is_not_checked() {
+        let report = Validation::new().with_atlas_size(4).validate(&animation());
+        assert!(report.is_empty());
+    }
+}
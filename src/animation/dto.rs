@@ -1,203 +1,683 @@
 use std::{
-    error::Error,
-    fmt::{self, Display, Formatter},
+    collections::BTreeMap,
+    fmt::{self, Formatter},
+    marker::PhantomData,
+    num::NonZeroU32,
     time::Duration,
 };
 
 use serde::{
-    de::{self, value::MapAccessDeserializer, MapAccess, Unexpected},
+    de::{
+        self,
+        value::{
+            BorrowedStrDeserializer, EnumAccessDeserializer, MapAccessDeserializer,
+            SeqAccessDeserializer,
+        },
+        EnumAccess, IntoDeserializer, MapAccess, SeqAccess,
+    },
     Deserialize, Serialize,
 };
 
-use super::{Animation, Frame, Mode};
+use super::{Animation, AnimationSet, Frame, FrameMetadata, FrameRate, Marker, Mode, TickRate};
+use crate::Error;
 
 #[derive(Serialize, Deserialize)]
-pub(super) struct AnimationDto {
-    #[serde(default)]
-    mode: ModeDto,
+#[serde(bound(deserialize = "T: Deserialize<'de>"))]
+pub(super) struct AnimationDto<T> {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    mode: Option<ModeDto>,
     #[serde(default, skip_serializing)]
     frame_duration: Option<u64>,
     #[serde(default, skip_serializing)]
     fps: Option<u64>,
     #[serde(default, skip_serializing)]
     total_duration: Option<u64>,
-    frames: Vec<FrameDto>,
+    #[serde(default, skip_serializing)]
+    frame_ticks: Option<NonZeroU32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    tick_rate: Option<NonZeroU32>,
+    frames: Vec<FrameDto<T>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    next: Option<String>,
 }
 
-#[derive(Serialize, Deserialize, Default)]
+#[derive(Serialize, Deserialize)]
+#[serde(bound(deserialize = "T: Deserialize<'de>"))]
+pub(super) struct AnimationSetDto<T> {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    mode: Option<ModeDto>,
+    #[serde(default, skip_serializing)]
+    frame_duration: Option<u64>,
+    #[serde(default, skip_serializing)]
+    fps: Option<u64>,
+    #[serde(default, skip_serializing)]
+    total_duration: Option<u64>,
+    #[serde(default, skip_serializing)]
+    frame_ticks: Option<NonZeroU32>,
+    #[serde(default, skip_serializing)]
+    tick_rate: Option<NonZeroU32>,
+    animations: BTreeMap<String, AnimationDto<T>>,
+}
+
+#[derive(Serialize, Deserialize, Default, Copy, Clone)]
 enum ModeDto {
     #[default]
     Repeat,
     RepeatFrom(usize),
     Once,
     PingPong,
+    RepeatTimes(NonZeroU32),
+    RepeatFromTimes {
+        from: usize,
+        times: NonZeroU32,
+    },
+    PingPongTimes(NonZeroU32),
+    Sustain {
+        loop_start: usize,
+        loop_end: usize,
+    },
 }
 
 #[derive(Serialize)]
-struct FrameDto {
-    index: usize,
+struct FrameDto<T> {
+    index: T,
+    #[serde(skip_serializing_if = "Option::is_none")]
     duration: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ticks: Option<NonZeroU32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    offset: Option<(f32, f32)>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pivot: Option<(f32, f32)>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    flip_x: bool,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    flip_y: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rotation: Option<f32>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    events: Vec<MarkerDto>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum MarkerDto {
+    Name(String),
+    At { name: String, offset: u64 },
+}
+
+/// Implement the visitor methods that deserialize the frame value alone, and wrap it with `$wrap`
+///
+/// Only the given scalar methods are implemented, along with the ones of the borrowed strings, unit, sequences and enums.
+macro_rules! forward_to_value {
+    ($wrap:expr; $($method:ident($ty:ty)),* $(,)?) => {
+        $(
+            fn $method<E>(self, v: $ty) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                T::deserialize(v.into_deserializer()).map($wrap)
+            }
+        )*
+
+        fn visit_borrowed_str<E>(self, v: &'de str) -> Result<Self::Value, E>
+        where
+            E: de::Error,
+        {
+            T::deserialize(BorrowedStrDeserializer::new(v)).map($wrap)
+        }
+
+        fn visit_unit<E>(self) -> Result<Self::Value, E>
+        where
+            E: de::Error,
+        {
+            T::deserialize(().into_deserializer()).map($wrap)
+        }
+
+        fn visit_seq<A>(self, seq: A) -> Result<Self::Value, A::Error>
+        where
+            A: SeqAccess<'de>,
+        {
+            T::deserialize(SeqAccessDeserializer::new(seq)).map($wrap)
+        }
+
+        fn visit_enum<A>(self, data: A) -> Result<Self::Value, A::Error>
+        where
+            A: EnumAccess<'de>,
+        {
+            T::deserialize(EnumAccessDeserializer::new(data)).map($wrap)
+        }
+    };
 }
 
-impl<'de> Deserialize<'de> for FrameDto {
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for FrameDto<T> {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
         D: serde::Deserializer<'de>,
     {
-        struct Visitor;
+        deserializer.deserialize_any(FrameVisitor(PhantomData))
+    }
+}
 
-        #[derive(Deserialize)]
-        #[serde(deny_unknown_fields)]
-        struct FrameDtoMap {
-            index: usize,
-            duration: Option<u64>,
+/// Visitor of a frame, which is either its value alone (short-hand notation), or a map of its properties
+struct FrameVisitor<T>(PhantomData<T>);
+
+impl<'de, T: Deserialize<'de>> de::Visitor<'de> for FrameVisitor<T> {
+    type Value = FrameDto<T>;
+
+    fn expecting(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            formatter,
+            "either a frame value, or a map of the frame value and properties"
+        )
+    }
+
+    forward_to_value! {
+        FrameDto::from_value;
+        visit_bool(bool),
+        visit_i64(i64),
+        visit_u64(u64),
+        visit_f64(f64),
+        visit_char(char),
+        visit_str(&str),
+        visit_string(String),
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let (mut index, mut atlas) = (None, None);
+        let (mut duration, mut ticks) = (None, None);
+        let (mut offset, mut pivot, mut rotation) = (None, None, None);
+        let (mut flip_x, mut flip_y, mut events) = (None, None, None);
+        while let Some(field) = map.next_key()? {
+            match field {
+                FrameField::Index => {
+                    set_once(&mut index, "index", map.next_value::<IndexDto<T>>()?)?;
+                }
+                FrameField::Atlas => set_once(&mut atlas, "atlas", map.next_value::<String>()?)?,
+                FrameField::Duration => set_once(&mut duration, "duration", map.next_value()?)?,
+                FrameField::Ticks => set_once(&mut ticks, "ticks", map.next_value()?)?,
+                FrameField::Offset => set_once(&mut offset, "offset", map.next_value()?)?,
+                FrameField::Pivot => set_once(&mut pivot, "pivot", map.next_value()?)?,
+                FrameField::FlipX => set_once(&mut flip_x, "flip_x", map.next_value()?)?,
+                FrameField::FlipY => set_once(&mut flip_y, "flip_y", map.next_value()?)?,
+                FrameField::Rotation => set_once(&mut rotation, "rotation", map.next_value()?)?,
+                FrameField::Events => set_once(&mut events, "events", map.next_value()?)?,
+            }
         }
+        let index = match (index, atlas) {
+            (None, _) => return Err(de::Error::missing_field("index")),
+            (Some(IndexDto::Value(value)), None) => value,
+            (Some(IndexDto::Number(index)), None) => T::deserialize(index.into_deserializer())?,
+            (Some(IndexDto::Number(index)), Some(atlas)) => {
+                T::deserialize(MapAccessDeserializer::new(AtlasIndexAccess {
+                    atlas: Some(atlas),
+                    index: Some(index),
+                    _error: PhantomData,
+                }))?
+            }
+            (Some(IndexDto::Value(_)), Some(_)) => {
+                return Err(de::Error::custom(
+                    "the index of a frame with an `atlas` must be a positive integer",
+                ))
+            }
+        };
+        if duration.is_some() && ticks.is_some() {
+            return Err(de::Error::custom(
+                "a frame cannot have both a duration and ticks",
+            ));
+        }
+        Ok(FrameDto {
+            index,
+            duration,
+            ticks,
+            offset,
+            pivot,
+            flip_x: flip_x.unwrap_or_default(),
+            flip_y: flip_y.unwrap_or_default(),
+            rotation,
+            events: events.unwrap_or_default(),
+        })
+    }
+}
 
-        impl<'de> de::Visitor<'de> for Visitor {
-            type Value = FrameDto;
+/// Keys of a frame written as a map
+#[derive(Deserialize)]
+#[serde(field_identifier, rename_all = "snake_case")]
+enum FrameField {
+    #[serde(alias = "value")]
+    Index,
+    Atlas,
+    Duration,
+    Ticks,
+    Offset,
+    Pivot,
+    FlipX,
+    FlipY,
+    Rotation,
+    Events,
+}
+
+/// Set the property of a frame, unless it was already set
+fn set_once<T, E: de::Error>(
+    property: &mut Option<T>,
+    name: &'static str,
+    value: T,
+) -> Result<(), E> {
+    if property.is_some() {
+        return Err(E::duplicate_field(name));
+    }
+    *property = Some(value);
+    Ok(())
+}
+
+/// The `index` (or `value`) of a frame written as a map
+///
+/// A number is kept as is, since it is the index in the atlas if the frame also has an `atlas`.
+enum IndexDto<T> {
+    Number(u64),
+    Value(T),
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for IndexDto<T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct Visitor<T>(PhantomData<T>);
+
+        impl<'de, T: Deserialize<'de>> de::Visitor<'de> for Visitor<T> {
+            type Value = IndexDto<T>;
 
             fn expecting(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
-                write!(formatter, "either a frame index, or a frame-index with a")
+                write!(formatter, "a frame value")
             }
 
-            fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
-            where
-                E: de::Error,
-            {
-                v.try_into()
-                    .map(|index| FrameDto {
-                        index,
-                        duration: None,
-                    })
-                    .map_err(|_| de::Error::invalid_value(Unexpected::Unsigned(v), &self))
+            forward_to_value! {
+                IndexDto::Value;
+                visit_bool(bool),
+                visit_i64(i64),
+                visit_f64(f64),
+                visit_char(char),
+                visit_str(&str),
+                visit_string(String),
             }
 
-            fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
+            fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
             where
                 E: de::Error,
             {
-                v.try_into()
-                    .map(|index| FrameDto {
-                        index,
-                        duration: None,
-                    })
-                    .map_err(|_| de::Error::invalid_value(Unexpected::Signed(v), &self))
+                Ok(IndexDto::Number(v))
             }
 
             fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
             where
                 A: MapAccess<'de>,
             {
-                let FrameDtoMap { index, duration } =
-                    FrameDtoMap::deserialize(MapAccessDeserializer::new(map))?;
-                Ok(FrameDto { index, duration })
+                T::deserialize(MapAccessDeserializer::new(map)).map(IndexDto::Value)
             }
         }
-        deserializer.deserialize_any(Visitor)
+
+        deserializer.deserialize_any(Visitor(PhantomData))
     }
 }
 
-impl From<Frame> for FrameDto {
-    fn from(frame: Frame) -> Self {
+/// Map of the `atlas` and `index` of a frame, from which the frame value is deserialized
+struct AtlasIndexAccess<E> {
+    atlas: Option<String>,
+    index: Option<u64>,
+    _error: PhantomData<E>,
+}
+
+impl<'de, E: de::Error> MapAccess<'de> for AtlasIndexAccess<E> {
+    type Error = E;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, E>
+    where
+        K: de::DeserializeSeed<'de>,
+    {
+        let key = if self.atlas.is_some() {
+            "atlas"
+        } else if self.index.is_some() {
+            "index"
+        } else {
+            return Ok(None);
+        };
+        seed.deserialize(key.into_deserializer()).map(Some)
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, E>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        match (self.atlas.take(), self.index.take()) {
+            (Some(atlas), index) => {
+                self.index = index;
+                seed.deserialize(atlas.into_deserializer())
+            }
+            (None, Some(index)) => seed.deserialize(index.into_deserializer()),
+            (None, None) => Err(E::custom("no more value")),
+        }
+    }
+}
+
+impl<T> FrameDto<T> {
+    /// Frame of the given value, without any property (short-hand notation)
+    fn from_value(index: T) -> Self {
+        Self {
+            index,
+            duration: None,
+            ticks: None,
+            offset: None,
+            pivot: None,
+            flip_x: false,
+            flip_y: false,
+            rotation: None,
+            events: Vec::new(),
+        }
+    }
+
+    /// Convert the frame, with its number of ticks if it is tick-based at the `tick_rate` of the animation
+    fn new(frame: Frame<T>, tick_rate: Option<TickRate>) -> Self {
+        let metadata = frame.metadata;
+        let ticks = frame
+            .ticks
+            .filter(|&(_, rate)| Some(rate) == tick_rate)
+            .map(|(ticks, _)| ticks);
         Self {
-            duration: Some(frame.duration.as_millis().try_into().unwrap()),
-            index: frame.index,
+            duration: match ticks {
+                Some(_) => None,
+                None => Some(frame.duration.as_millis().try_into().unwrap()),
+            },
+            ticks,
+            index: frame.value,
+            offset: Some(metadata.offset()).filter(|&offset| offset != (0.0, 0.0)),
+            pivot: metadata.pivot(),
+            flip_x: metadata.flip_x(),
+            flip_y: metadata.flip_y(),
+            rotation: Some(metadata.rotation()).filter(|&rotation| rotation != 0.0),
+            events: Vec::new(),
         }
     }
+
+    fn metadata(&self) -> FrameMetadata {
+        let mut metadata = FrameMetadata::new()
+            .with_flip_x(self.flip_x)
+            .with_flip_y(self.flip_y);
+        if let Some((x, y)) = self.offset {
+            metadata = metadata.with_offset(x, y);
+        }
+        if let Some((x, y)) = self.pivot {
+            metadata = metadata.with_pivot(x, y);
+        }
+        if let Some(rotation) = self.rotation {
+            metadata = metadata.with_rotation(rotation);
+        }
+        metadata
+    }
 }
 
-impl From<Animation> for AnimationDto {
-    fn from(animation: Animation) -> Self {
+impl<T> From<Animation<T>> for AnimationDto<T> {
+    fn from(animation: Animation<T>) -> Self {
+        let tick_rate = animation
+            .frames
+            .iter()
+            .find_map(|frame| frame.ticks)
+            .map(|(_, tick_rate)| tick_rate);
         Self {
             frame_duration: None,
             fps: None,
             total_duration: None,
-            mode: match animation.mode {
+            frame_ticks: None,
+            tick_rate: tick_rate.map(TickRate::hz),
+            mode: Some(match animation.mode {
                 Mode::Once => ModeDto::Once,
                 Mode::RepeatFrom(0) => ModeDto::Repeat,
                 Mode::RepeatFrom(i) => ModeDto::RepeatFrom(i),
                 Mode::PingPong => ModeDto::PingPong,
+                Mode::RepeatTimes { from: 0, times } => ModeDto::RepeatTimes(times),
+                Mode::RepeatTimes { from, times } => ModeDto::RepeatFromTimes { from, times },
+                Mode::PingPongTimes(times) => ModeDto::PingPongTimes(times),
+                Mode::Sustain {
+                    loop_start,
+                    loop_end,
+                } => ModeDto::Sustain {
+                    loop_start,
+                    loop_end,
+                },
+            }),
+            frames: {
+                let mut frames: Vec<FrameDto<T>> = animation
+                    .frames
+                    .into_iter()
+                    .map(|frame| FrameDto::new(frame, tick_rate))
+                    .collect();
+                for marker in animation.markers {
+                    if let Some(frame) = frames.get_mut(marker.position) {
+                        frame.events.push(marker.into());
+                    }
+                }
+                frames
             },
-            frames: animation.frames.into_iter().map(FrameDto::from).collect(),
+            next: animation.next,
         }
     }
 }
 
-impl AnimationDto {
-    #[allow(clippy::cast_precision_loss)]
-    fn default_duration(&self) -> Result<Option<Duration>, InvalidAnimation> {
-        match (
-            self.frame_duration.map(Duration::from_millis),
-            self.total_duration
-                .map(Duration::from_millis)
-                .map(|d| d.div_f64(self.frames.len() as f64)),
-            self.fps
-                .map(|fps| Duration::from_secs(1).div_f64(fps as f64)),
-        ) {
-            (duration, None, None) | (None, duration, None) | (None, None, duration) => {
-                Ok(duration)
+impl From<Marker> for MarkerDto {
+    fn from(Marker { offset, name, .. }: Marker) -> Self {
+        if offset.is_zero() {
+            Self::Name(name)
+        } else {
+            Self::At {
+                name,
+                offset: offset.as_millis().try_into().unwrap(),
             }
-            _ => Err(InvalidAnimation::IncompatibleFrameRate),
         }
     }
 }
 
-impl TryFrom<AnimationDto> for Animation {
-    type Error = InvalidAnimation;
+impl MarkerDto {
+    fn into_marker(self, position: usize) -> Marker {
+        let (name, offset) = match self {
+            MarkerDto::Name(name) => (name, Duration::ZERO),
+            MarkerDto::At { name, offset } => (name, Duration::from_millis(offset)),
+        };
+        Marker {
+            position,
+            offset,
+            name,
+        }
+    }
+}
 
-    fn try_from(animation: AnimationDto) -> Result<Self, Self::Error> {
-        let default_duration = animation.default_duration()?;
-        let frames: Vec<Frame> = animation
+impl<T> AnimationDto<T> {
+    #[allow(clippy::cast_precision_loss)]
+    fn default_frame_rate(&self, tick_rate: Option<TickRate>) -> Result<Option<FrameRate>, Error> {
+        let frame_rate = match (
+            self.frame_duration,
+            self.total_duration,
+            self.fps,
+            self.frame_ticks,
+        ) {
+            (None, None, None, None) => return Ok(None),
+            (Some(duration), None, None, None) => {
+                FrameRate::from_frame_duration(Duration::from_millis(duration))
+            }
+            (None, Some(duration), None, None) => {
+                FrameRate::from_total_duration(Duration::from_millis(duration))
+            }
+            (None, None, Some(fps), None) => FrameRate::try_from_fps(fps as f64)?,
+            (None, None, None, Some(ticks)) => {
+                FrameRate::from_ticks(ticks, tick_rate.ok_or(Error::MissingTickRate)?)
+            }
+            _ => return Err(Error::IncompatibleFrameRate),
+        };
+        Ok(Some(frame_rate))
+    }
+
+    fn into_animation(self) -> Result<Animation<T>, Error> {
+        let tick_rate = self.tick_rate.map(TickRate::try_from_hz).transpose()?;
+        let default_frame_rate = self.default_frame_rate(tick_rate)?;
+        let frame_count = self.frames.len();
+        let mut markers = Vec::new();
+        let frames: Vec<Frame<T>> = self
             .frames
             .into_iter()
-            .map(|FrameDto { index, duration }| {
-                let duration = duration
-                    .map(Duration::from_millis)
-                    .or(default_duration)
-                    .filter(|d| !d.is_zero());
-                match duration {
-                    Some(duration) => Ok(Frame::new(index, duration)),
-                    None => Err(InvalidAnimation::ZeroDuration),
-                }
+            .enumerate()
+            .map(|(position, mut frame)| {
+                let metadata = frame.metadata();
+                let events = core::mem::take(&mut frame.events);
+                markers.extend(events.into_iter().map(|e| e.into_marker(position)));
+                let frame = match (frame.duration, frame.ticks, default_frame_rate) {
+                    (Some(duration), _, _) => {
+                        Frame::try_from_value(frame.index, Duration::from_millis(duration))?
+                    }
+                    (None, Some(ticks), _) => Frame::from_ticks(
+                        frame.index,
+                        ticks,
+                        tick_rate.ok_or(Error::MissingTickRate)?,
+                    ),
+                    (
+                        None,
+                        None,
+                        Some(FrameRate {
+                            ticks: Some((ticks, tick_rate)),
+                            ..
+                        }),
+                    ) => Frame::from_ticks(frame.index, ticks, tick_rate),
+                    (None, None, frame_rate) => Frame::try_from_value(
+                        frame.index,
+                        frame_rate.map_or(Duration::ZERO, |frame_rate| {
+                            frame_rate.frame_duration_for(frame_count)
+                        }),
+                    )?,
+                };
+                Ok(frame.with_metadata(metadata))
             })
-            .collect::<Result<_, _>>()?;
+            .collect::<Result<_, Error>>()?;
+        markers.sort_by_key(|m| (m.position, m.offset));
 
-        Ok(Self {
-            frames,
-            mode: match animation.mode {
+        Ok(Animation {
+            markers,
+            mode: match self.mode.unwrap_or_default() {
                 ModeDto::Repeat => Mode::RepeatFrom(0),
                 ModeDto::RepeatFrom(f) => Mode::RepeatFrom(f),
                 ModeDto::Once => Mode::Once,
                 ModeDto::PingPong => Mode::PingPong,
+                ModeDto::RepeatTimes(times) => Mode::RepeatTimes { from: 0, times },
+                ModeDto::RepeatFromTimes { from, times } => Mode::RepeatTimes { from, times },
+                ModeDto::PingPongTimes(times) => Mode::PingPongTimes(times),
+                ModeDto::Sustain {
+                    loop_start,
+                    loop_end,
+                } if loop_start > loop_end => {
+                    return Err(Error::EmptyLoop {
+                        loop_start,
+                        loop_end,
+                    })
+                }
+                ModeDto::Sustain {
+                    loop_start,
+                    loop_end,
+                } => Mode::Sustain {
+                    loop_start,
+                    loop_end,
+                },
             },
+            next: self.next,
+            ..Animation::from_frames(frames)
         })
     }
 }
 
-#[derive(Debug)]
-pub(super) enum InvalidAnimation {
-    ZeroDuration,
-    IncompatibleFrameRate,
+impl<T> TryFrom<AnimationDto<T>> for Animation<T> {
+    type Error = Error;
+
+    fn try_from(animation: AnimationDto<T>) -> Result<Self, Self::Error> {
+        let result = animation.into_animation()?;
+        let report = result.validate();
+        if report.is_valid() {
+            Ok(result)
+        } else {
+            Err(Error::Invalid(report))
+        }
+    }
 }
 
-impl Display for InvalidAnimation {
-    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        match self {
-            InvalidAnimation::ZeroDuration => write!(f, "invalid duration, must be > 0"),
-            InvalidAnimation::IncompatibleFrameRate => {
-                write!(f, "fps is incompatible with frame_duration")
-            }
+impl<T> From<AnimationSet<T>> for AnimationSetDto<T> {
+    fn from(set: AnimationSet<T>) -> Self {
+        Self {
+            mode: None,
+            frame_duration: None,
+            fps: None,
+            total_duration: None,
+            frame_ticks: None,
+            tick_rate: None,
+            animations: set
+                .animations
+                .into_iter()
+                .map(|(name, animation)| (name, animation.into()))
+                .collect(),
         }
     }
 }
 
-impl Error for InvalidAnimation {}
+impl<T> TryFrom<AnimationSetDto<T>> for AnimationSet<T> {
+    type Error = Error;
+
+    fn try_from(set: AnimationSetDto<T>) -> Result<Self, Self::Error> {
+        let result: Self = set
+            .animations
+            .into_iter()
+            .map(|(name, mut animation)| {
+                if animation.mode.is_none() {
+                    animation.mode = set.mode;
+                }
+                if animation.frame_duration.is_none()
+                    && animation.fps.is_none()
+                    && animation.total_duration.is_none()
+                    && animation.frame_ticks.is_none()
+                {
+                    animation.frame_duration = set.frame_duration;
+                    animation.fps = set.fps;
+                    animation.total_duration = set.total_duration;
+                    animation.frame_ticks = set.frame_ticks;
+                }
+                if animation.tick_rate.is_none() {
+                    animation.tick_rate = set.tick_rate;
+                }
+                match Animation::try_from(animation) {
+                    Ok(animation) => Ok((name, animation)),
+                    Err(error) => Err(Error::InvalidAnimation {
+                        name,
+                        source: Box::new(error),
+                    }),
+                }
+            })
+            .collect::<Result<_, _>>()?;
+        for (name, animation) in &result.animations {
+            if let Some(next) = animation
+                .next()
+                .filter(|next| !result.animations.contains_key(*next))
+            {
+                return Err(Error::InvalidAnimation {
+                    name: name.clone(),
+                    source: Box::new(Error::UnknownAnimation(next.into())),
+                });
+            }
+        }
+        Ok(result)
+    }
+}
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    use crate::{animation::Mode, Frame, FrameRate};
+    use crate::{animation::Mode, AtlasIndex, Frame, FrameRate};
     use std::time::Duration;
 
     #[rstest]
@@ -208,6 +688,17 @@ mod tests {
             Animation::from_indices(0..=2, FrameRate::from_fps(2.0)).repeat(),
             Animation::from_indices(0..=2, FrameRate::from_fps(2.0)).repeat_from(1),
             Animation::from_indices(0..=2, FrameRate::from_fps(2.0)).ping_pong(),
+            Animation::from_indices(0..=2, FrameRate::from_fps(2.0))
+                .repeat_times(NonZeroU32::new(3).unwrap()),
+            Animation::from_indices(0..=2, FrameRate::from_fps(2.0))
+                .repeat_from_times(1, NonZeroU32::new(3).unwrap()),
+            Animation::from_indices(0..=2, FrameRate::from_fps(2.0))
+                .ping_pong_times(NonZeroU32::new(2).unwrap()),
+            Animation::from_indices(0..=2, FrameRate::from_fps(2.0)).sustain(1..=1),
+            Animation::from_indices(0..=2, FrameRate::from_fps(2.0))
+                .with_marker(0, "a")
+                .with_marker_at(0, Duration::from_millis(100), "b")
+                .with_marker_at(2, Duration::from_millis(200), "c"),
         )]
         animation: Animation,
     ) {
@@ -312,6 +803,77 @@ mod tests {
         assert_eq!(animation.mode, Mode::RepeatFrom(1));
     }
 
+    #[rstest]
+    #[case("!RepeatTimes 3", Mode::RepeatTimes { from: 0, times: NonZeroU32::new(3).unwrap() })]
+    #[case(
+        "!RepeatFromTimes { from: 1, times: 2 }",
+        Mode::RepeatTimes { from: 1, times: NonZeroU32::new(2).unwrap() },
+    )]
+    #[case("!PingPongTimes 4", Mode::PingPongTimes(NonZeroU32::new(4).unwrap()))]
+    #[case(
+        "!Sustain { loop_start: 1, loop_end: 2 }",
+        Mode::Sustain { loop_start: 1, loop_end: 2 },
+    )]
+    fn finite_modes(#[case] mode: &str, #[case] expected: Mode) {
+        // given
+        let content = format!(
+            "
+            mode: {mode}
+            frames: [0, 1, 2, 3]
+            fps: 10"
+        );
+
+        // when
+        let animation: Animation = serde_yaml::from_str(&content).unwrap();
+
+        // then
+        assert_eq!(animation.mode, expected);
+    }
+
+    #[rstest]
+    #[case("!RepeatTimes 0")]
+    #[case("!Sustain { loop_start: 2, loop_end: 1 }")]
+    fn invalid_finite_modes(#[case] mode: &str) {
+        let content = format!(
+            "
+            mode: {mode}
+            frames: [0, 1, 2, 3]
+            fps: 10"
+        );
+        assert!(serde_yaml::from_str::<Animation>(&content).is_err());
+    }
+
+    #[test]
+    fn events() {
+        // given
+        let content = "
+            frame_duration: 100
+            frames:
+              - index: 0
+                events: [footstep, dust]
+              - index: 1
+              - index: 2
+                events:
+                  - name: spawn_projectile
+                    offset: 50
+        ";
+
+        // when
+        let animation: Animation = serde_yaml::from_str(content).unwrap();
+
+        // then
+        assert_eq!(
+            animation,
+            Animation::from_indices(
+                0..=2,
+                FrameRate::from_frame_duration(Duration::from_millis(100))
+            )
+            .with_marker(0, "footstep")
+            .with_marker(0, "dust")
+            .with_marker_at(2, Duration::from_millis(50), "spawn_projectile")
+        );
+    }
+
     #[test]
     fn zero_duration() {
         // given
@@ -391,6 +953,138 @@ mod tests {
         assert!(serde_yaml::from_str::<Animation>(content).is_err());
     }
 
+    #[rstest]
+    #[case::zero_fps("fps: 0\nframes: [0, 1]")]
+    #[case::zero_duration("frame_duration: 0\nframes: [0, 1]")]
+    fn invalid_frame_rate_is_error(#[case] content: &str) {
+        assert!(serde_yaml::from_str::<Animation>(content).is_err());
+    }
+
+    #[rstest]
+    #[case::no_frame("total_duration: 100\nframes: []", "the animation has no frame")]
+    #[case::repeat_from_out_of_range(
+        "mode: !RepeatFrom 5\nfps: 10\nframes: [0, 1]",
+        "the mode refers to frame 5, but there are only 2 frames"
+    )]
+    #[case::marker_after_frame_end(
+        "fps: 10\nframes: [{ index: 0, events: [{ name: a, offset: 100 }] }]",
+        "frame 0: marker offset of 100ms is not before the end of the frame (100ms)"
+    )]
+    #[case::non_finite_metadata(
+        "fps: 10\nframes: [{ index: 0, rotation: .nan }]",
+        "frame 0: the offset, pivot or rotation is not finite"
+    )]
+    fn validation_problems_are_errors(#[case] content: &str, #[case] message: &str) {
+        let error = serde_yaml::from_str::<Animation>(content).unwrap_err();
+        assert!(error.to_string().contains(message), "{error}");
+    }
+
+    #[test]
+    fn validation_reports_every_problem() {
+        let dto: AnimationDto<usize> = serde_yaml::from_str(
+            "mode: !Sustain { loop_start: 2, loop_end: 3 }\nfps: 10\nframes: [0, 1]",
+        )
+        .unwrap();
+        let Err(Error::Invalid(report)) = Animation::try_from(dto) else {
+            panic!("expected a validation error");
+        };
+        assert_eq!(report.problems().len(), 2);
+    }
+
+    #[test]
+    fn ticks() {
+        let content = "
+            tick_rate: 60
+            frame_ticks: 3
+            frames:
+              - 0
+              - index: 1
+                ticks: 5
+              - index: 2
+                duration: 100
+        ";
+        let animation: Animation = serde_yaml::from_str(content).unwrap();
+        let tick_rate = TickRate::from_hz(NonZeroU32::new(60).unwrap());
+        assert_eq!(
+            animation,
+            Animation::from_frames([
+                Frame::from_ticks(0, NonZeroU32::new(3).unwrap(), tick_rate),
+                Frame::from_ticks(1, NonZeroU32::new(5).unwrap(), tick_rate),
+                Frame::new(2, Duration::from_millis(100)),
+            ])
+        );
+        assert_eq!(
+            animation.frames()[0].ticks(),
+            Some((NonZeroU32::new(3).unwrap(), tick_rate))
+        );
+        assert_eq!(animation.frames()[2].ticks(), None);
+    }
+
+    #[test]
+    fn ticks_round_trip() {
+        let tick_rate = TickRate::from_hz(NonZeroU32::new(60).unwrap());
+        let animation = Animation::from_frames([
+            Frame::from_ticks(0, NonZeroU32::new(3).unwrap(), tick_rate),
+            Frame::new(1, Duration::from_millis(100)),
+        ]);
+        let yaml = serde_yaml::to_string(&animation).unwrap();
+        assert!(yaml.contains("tick_rate: 60"), "{yaml}");
+        assert!(yaml.contains("ticks: 3"), "{yaml}");
+        let deserialized: Animation = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(deserialized, animation);
+        assert_eq!(
+            deserialized.frames()[0].ticks(),
+            animation.frames()[0].ticks()
+        );
+    }
+
+    #[test]
+    fn ticks_of_the_set_use_the_tick_rate_of_the_set() {
+        let content = "
+            tick_rate: 50
+            frame_ticks: 2
+            animations:
+              idle:
+                frames: [0]
+              run:
+                tick_rate: 100
+                frames: [1]
+        ";
+        let set: AnimationSet = serde_yaml::from_str(content).unwrap();
+        let ticks = NonZeroU32::new(2).unwrap();
+        assert_eq!(
+            set.get("idle").unwrap().frames()[0].ticks(),
+            Some((ticks, TickRate::from_hz(NonZeroU32::new(50).unwrap())))
+        );
+        assert_eq!(
+            set.get("run").unwrap().frames()[0].ticks(),
+            Some((ticks, TickRate::from_hz(NonZeroU32::new(100).unwrap())))
+        );
+    }
+
+    #[rstest]
+    #[case::frame_ticks_and_frame_duration(
+        "tick_rate: 60\nframe_ticks: 3\nframe_duration: 3\nframes: [0]"
+    )]
+    #[case::ticks_and_duration("tick_rate: 60\nframes: [{ index: 0, ticks: 3, duration: 3 }]")]
+    #[case::zero_ticks("tick_rate: 60\nframes: [{ index: 0, ticks: 0 }]")]
+    fn invalid_ticks_is_error(#[case] content: &str) {
+        assert!(serde_yaml::from_str::<Animation>(content).is_err());
+    }
+
+    #[rstest]
+    #[case::frame_ticks("frame_ticks: 3\nframes: [0]", "the tick_rate must be defined")]
+    #[case::ticks("frames: [{ index: 0, ticks: 3 }]", "the tick_rate must be defined")]
+    #[case::zero_tick_rate("tick_rate: 0\nframe_ticks: 3\nframes: [0]", "nonzero")]
+    #[case::too_high_tick_rate(
+        "tick_rate: 2000000000\nframe_ticks: 3\nframes: [0]",
+        "invalid tick rate"
+    )]
+    fn invalid_tick_rate_is_error(#[case] content: &str, #[case] message: &str) {
+        let error = serde_yaml::from_str::<Animation>(content).unwrap_err();
+        assert!(error.to_string().contains(message), "{error}");
+    }
+
     #[test]
     fn fps_with_single_frame_duration() {
         let content = "
@@ -475,4 +1169,310 @@ mod tests {
             ]
         );
     }
+
+    #[rstest]
+    #[case::misspelled_index("frames: [{ idnex: 3, duration: 100 }]", "unknown field `idnex`")]
+    #[case::misspelled_property("frames: [{ index: 3, durtion: 100 }]", "unknown field `durtion`")]
+    #[case::missing_index("frames: [{ duration: 100 }]", "missing field `index`")]
+    #[case::invalid_index("frames: [{ index: -1 }]", "expected usize")]
+    #[case::duplicate_property(
+        "frames: [{ index: 3, duration: 100, duration: 200 }]",
+        "duplicate field `duration`"
+    )]
+    fn invalid_frame_error_message(#[case] content: &str, #[case] message: &str) {
+        let error = serde_yaml::from_str::<Animation>(&format!("fps: 10\n{content}")).unwrap_err();
+        assert!(error.to_string().contains(message), "{error}");
+    }
+
+    mod values {
+        use super::*;
+
+        #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+        struct Sprite {
+            #[serde(default)]
+            atlas: u8,
+            index: usize,
+        }
+
+        #[test]
+        fn short_hand() {
+            let content = "
+                fps: 10
+                frames: [[0, 1], [0, 2]]";
+            let animation: Animation<(u8, usize)> = serde_yaml::from_str(content).unwrap();
+            assert_eq!(
+                animation,
+                Animation::from_values([(0, 1), (0, 2)], FrameRate::from_fps(10.0))
+            );
+        }
+
+        #[test]
+        fn map() {
+            let content = "
+                frame_duration: 100
+                frames:
+                  - value: { atlas: 1, index: 0 }
+                  - index: { index: 1 }
+                    duration: 200
+                  - value: { index: 2 }";
+            let animation: Animation<Sprite> = serde_yaml::from_str(content).unwrap();
+            let sprite = |atlas, index| Sprite { atlas, index };
+            assert_eq!(
+                animation,
+                Animation::from_frames([
+                    Frame::from_value(sprite(1, 0), Duration::from_millis(100)),
+                    Frame::from_value(sprite(0, 1), Duration::from_millis(200)),
+                    Frame::from_value(sprite(0, 2), Duration::from_millis(100)),
+                ])
+            );
+        }
+
+        #[test]
+        fn atlas_index() {
+            let content = "
+                frame_duration: 100
+                frames:
+                  - { atlas: boss_a, index: 14 }
+                  - { atlas: boss_a, index: 15, duration: 200, flip_x: true }
+                  - index: { atlas: boss_b, index: 0 }
+                    duration: 50";
+            let animation: Animation<AtlasIndex> = serde_yaml::from_str(content).unwrap();
+            assert_eq!(
+                animation,
+                Animation::from_frames([
+                    Frame::from_value(AtlasIndex::new("boss_a", 14), Duration::from_millis(100)),
+                    Frame::from_value(AtlasIndex::new("boss_a", 15), Duration::from_millis(200))
+                        .with_metadata(FrameMetadata::new().with_flip_x(true)),
+                    Frame::from_value(AtlasIndex::new("boss_b", 0), Duration::from_millis(50)),
+                ])
+            );
+            let yaml = serde_yaml::to_string(&animation).unwrap();
+            assert_eq!(
+                serde_yaml::from_str::<Animation<AtlasIndex>>(&yaml).unwrap(),
+                animation
+            );
+        }
+
+        #[test]
+        fn atlas_after_index() {
+            let content = "
+                frame_duration: 100
+                frames: [{ index: 3, duration: 50, atlas: boss_b }]";
+            let animation: Animation<AtlasIndex> = serde_yaml::from_str(content).unwrap();
+            assert_eq!(
+                animation,
+                Animation::from_frames([Frame::from_value(
+                    AtlasIndex::new("boss_b", 3),
+                    Duration::from_millis(50)
+                )])
+            );
+        }
+
+        #[test]
+        fn large_index_in_json() {
+            let content = r#"{ "frame_duration": 100, "frames": [{ "index": 9007199254740993 }] }"#;
+            let animation: Animation = serde_json::from_str(content).unwrap();
+            assert_eq!(animation.frames()[0].index(), 9_007_199_254_740_993);
+        }
+
+        #[rstest]
+        #[case::missing_atlas("frames: [{ index: 0 }]")]
+        #[case::ticks_and_duration(
+            "tick_rate: 60\nframes: [{ atlas: a, index: 0, ticks: 3, duration: 3 }]"
+        )]
+        fn invalid_atlas_index(#[case] content: &str) {
+            assert!(serde_yaml::from_str::<Animation<AtlasIndex>>(content).is_err());
+        }
+
+        #[rstest]
+        #[case::misspelled_index(
+            "frames: [{ atlas: a, idnex: 3, duration: 100 }]",
+            "unknown field `idnex`"
+        )]
+        #[case::misspelled_property(
+            "frames: [{ atlas: a, index: 3, durtion: 100 }]",
+            "unknown field `durtion`"
+        )]
+        #[case::missing_atlas(
+            "frames: [{ index: 3, duration: 100 }]",
+            "expected struct AtlasIndex"
+        )]
+        fn invalid_atlas_index_error_message(#[case] content: &str, #[case] message: &str) {
+            let error =
+                serde_yaml::from_str::<Animation<AtlasIndex>>(&format!("fps: 10\n{content}"))
+                    .unwrap_err();
+            assert!(error.to_string().contains(message), "{error}");
+        }
+
+        #[test]
+        fn deserialize_serialize() {
+            let animation = Animation::from_values(['|', '/', '-', '\\'], FrameRate::from_fps(8.0))
+                .ping_pong()
+                .with_marker(2, "a");
+            let yaml: String = serde_yaml::to_string(&animation).unwrap();
+            assert_eq!(
+                serde_yaml::from_str::<Animation<char>>(&yaml).unwrap(),
+                animation
+            );
+            let json: String = serde_json::to_string(&animation).unwrap();
+            assert_eq!(
+                serde_json::from_str::<Animation<char>>(&json).unwrap(),
+                animation
+            );
+        }
+
+        #[test]
+        fn set() {
+            let content = "
+                fps: 10
+                animations:
+                  spin:
+                    frames: ['|', '/', '-']";
+            let set: AnimationSet<char> = serde_yaml::from_str(content).unwrap();
+            assert_eq!(
+                set.get("spin"),
+                Ok(&Animation::from_values(
+                    ['|', '/', '-'],
+                    FrameRate::from_fps(10.0)
+                ))
+            );
+        }
+    }
+
+    #[test]
+    fn metadata() {
+        let content = "
+            frame_duration: 100
+            frames:
+              - 0
+              - index: 1
+                offset: [2, -1.5]
+                pivot: [-0.5, -0.5]
+                flip_x: true
+                flip_y: true
+                rotation: 1.5";
+        let animation: Animation = serde_yaml::from_str(content).unwrap();
+        assert_eq!(animation.frames()[0].metadata(), &FrameMetadata::new());
+        assert_eq!(
+            animation.frames()[1].metadata(),
+            &FrameMetadata::new()
+                .with_offset(2.0, -1.5)
+                .with_pivot(-0.5, -0.5)
+                .with_flip_x(true)
+                .with_flip_y(true)
+                .with_rotation(1.5)
+        );
+        let yaml = serde_yaml::to_string(&animation).unwrap();
+        assert_eq!(serde_yaml::from_str::<Animation>(&yaml).unwrap(), animation);
+        let toml = toml::to_string(&animation).unwrap();
+        assert_eq!(toml::from_str::<Animation>(&toml).unwrap(), animation);
+    }
+
+    mod set {
+        use super::*;
+
+        #[test]
+        fn deserialize_with_defaults() {
+            // given
+            let content = "
+                mode: Once
+                fps: 10
+                animations:
+                  idle:
+                    frames: [0, 1]
+                  walk:
+                    mode: PingPong
+                    frame_duration: 200
+                    frames: [2, 3]";
+
+            // when
+            let set: AnimationSet = serde_yaml::from_str(content).unwrap();
+
+            // then
+            assert_eq!(
+                set.get("idle"),
+                Ok(&Animation::from_indices(0..=1, FrameRate::from_fps(10.0)).once())
+            );
+            assert_eq!(
+                set.get("walk"),
+                Ok(&Animation::from_indices(
+                    2..=3,
+                    FrameRate::from_frame_duration(Duration::from_millis(200))
+                )
+                .ping_pong())
+            );
+        }
+
+        #[test]
+        fn deserialize_serialize() {
+            let set = AnimationSet::new()
+                .with_animation(
+                    "idle",
+                    Animation::from_indices(0..=2, FrameRate::from_fps(2.0)),
+                )
+                .with_animation(
+                    "attack",
+                    Animation::from_indices(3..=4, FrameRate::from_fps(4.0))
+                        .once()
+                        .with_marker(1, "hit")
+                        .with_next("idle"),
+                );
+            let yaml: String = serde_yaml::to_string(&set).unwrap();
+            assert_eq!(serde_yaml::from_str::<AnimationSet>(&yaml).unwrap(), set);
+            let toml: String = toml::to_string(&set).unwrap();
+            assert_eq!(toml::from_str::<AnimationSet>(&toml).unwrap(), set);
+            let json: String = serde_json::to_string(&set).unwrap();
+            assert_eq!(serde_json::from_str::<AnimationSet>(&json).unwrap(), set);
+        }
+
+        #[test]
+        fn deserialize_json() {
+            let content = r#"{ "fps": 10, "animations": { "idle": { "frames": [0, 1] } } }"#;
+            let set: AnimationSet = serde_json::from_str(content).unwrap();
+            assert_eq!(
+                set.get("idle"),
+                Ok(&Animation::from_indices(0..=1, FrameRate::from_fps(10.0)))
+            );
+        }
+
+        #[test]
+        fn error_names_invalid_animation() {
+            let content = "
+                animations:
+                  idle:
+                    fps: 10
+                    frame_duration: 100
+                    frames: [0, 1]";
+            let error = serde_yaml::from_str::<AnimationSet>(content).unwrap_err();
+            assert!(error.to_string().contains("\"idle\""), "{error}");
+        }
+
+        #[test]
+        fn deserialize_next() {
+            let content = "
+                fps: 10
+                animations:
+                  attack:
+                    mode: Once
+                    frames: [0, 1]
+                    next: idle
+                  idle:
+                    frames: [2, 3]";
+            let set: AnimationSet = serde_yaml::from_str(content).unwrap();
+            assert_eq!(set.get("attack").unwrap().next(), Some("idle"));
+            assert_eq!(set.get("idle").unwrap().next(), None);
+        }
+
+        #[test]
+        fn error_on_unknown_next() {
+            let content = "
+                fps: 10
+                animations:
+                  attack:
+                    frames: [0, 1]
+                    next: recover";
+            let error = serde_yaml::from_str::<AnimationSet>(content).unwrap_err();
+            assert!(error.to_string().contains("\"recover\""), "{error}");
+        }
+    }
 }
@@ -0,0 +1,181 @@
+use core::hash::{Hash, Hasher};
+
+/// How the sprite of a frame is placed when drawn
+///
+/// It is useful for trimmed sprite sheets, where the sprites don't all have the same size and position,
+/// and for animations that reuse sprites flipped or rotated.
+///
+/// The default metadata has no offset, no pivot, no flip and no rotation.
+///
+/// See [`Frame::with_metadata`](crate::Frame::with_metadata) and
+/// [`State::frame_metadata`](crate::State::frame_metadata)
+///
+/// # Example
+///
+/// ```
+/// # use std::time::Duration;
+/// use benimator::*;
+///
+/// let animation = Animation::from_frames([
+///     Frame::new(0, Duration::from_millis(100)),
+///     Frame::new(0, Duration::from_millis(100))
+///         .with_metadata(FrameMetadata::new().with_offset(2.0, 0.0).with_flip_x(true)),
+/// ]);
+///
+/// let mut state = State::new();
+/// state.update(&animation, Duration::from_millis(150));
+/// assert_eq!(state.frame_metadata(&animation).offset(), (2.0, 0.0));
+/// assert!(state.frame_metadata(&animation).flip_x());
+/// ```
+#[derive(Debug, Copy, Clone, Default)]
+#[must_use]
+pub struct FrameMetadata {
+    offset: (f32, f32),
+    pivot: Option<(f32, f32)>,
+    flip_x: bool,
+    flip_y: bool,
+    rotation: f32,
+}
+
+impl FrameMetadata {
+    /// Create the default metadata
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the offset at which the sprite is drawn, in world units (with the y axis pointing up)
+    pub fn with_offset(mut self, x: f32, y: f32) -> Self {
+        self.offset = (x, y);
+        self
+    }
+
+    /// Set the pivot point of the sprite, relative to its size
+    ///
+    /// The center of the sprite is `(0.0, 0.0)`, its bottom-left corner is `(-0.5, -0.5)`,
+    /// and its top-right corner is `(0.5, 0.5)`.
+    pub fn with_pivot(mut self, x: f32, y: f32) -> Self {
+        self.pivot = Some((x, y));
+        self
+    }
+
+    /// Set whether the sprite is flipped horizontally
+    pub fn with_flip_x(mut self, flip_x: bool) -> Self {
+        self.flip_x = flip_x;
+        self
+    }
+
+    /// Set whether the sprite is flipped vertically
+    pub fn with_flip_y(mut self, flip_y: bool) -> Self {
+        self.flip_y = flip_y;
+        self
+    }
+
+    /// Set the rotation of the sprite, in radians (counter-clockwise)
+    pub fn with_rotation(mut self, rotation: f32) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    /// Returns the offset at which the sprite is drawn
+    #[must_use]
+    pub fn offset(&self) -> (f32, f32) {
+        self.offset
+    }
+
+    /// Returns the pivot point of the sprite, if it has one
+    #[must_use]
+    pub fn pivot(&self) -> Option<(f32, f32)> {
+        self.pivot
+    }
+
+    /// Returns true if the sprite is flipped horizontally
+    #[must_use]
+    pub fn flip_x(&self) -> bool {
+        self.flip_x
+    }
+
+    /// Returns true if the sprite is flipped vertically
+    #[must_use]
+    pub fn flip_y(&self) -> bool {
+        self.flip_y
+    }
+
+    /// Returns the rotation of the sprite, in radians
+    #[must_use]
+    pub fn rotation(&self) -> f32 {
+        self.rotation
+    }
+
+    /// Returns true if this is the default metadata
+    #[must_use]
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Returns true if the offset, the pivot and the rotation are all finite
+    pub(crate) fn is_finite(&self) -> bool {
+        let finite = |(x, y): (f32, f32)| x.is_finite() && y.is_finite();
+        finite(self.offset) && self.pivot.map_or(true, finite) && self.rotation.is_finite()
+    }
+
+    /// Returns the fields compared and hashed, with the floats as their bit patterns
+    #[allow(clippy::type_complexity)]
+    fn key(&self) -> ((u32, u32), Option<(u32, u32)>, bool, bool, u32) {
+        let bits = |(x, y): (f32, f32)| (x.to_bits(), y.to_bits());
+        (
+            bits(self.offset),
+            self.pivot.map(bits),
+            self.flip_x,
+            self.flip_y,
+            self.rotation.to_bits(),
+        )
+    }
+}
+
+impl PartialEq for FrameMetadata {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for FrameMetadata {}
+
+impl Hash for FrameMetadata {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.key().hash(state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builders() {
+        let metadata = FrameMetadata::new()
+            .with_offset(1.0, -2.0)
+            .with_pivot(-0.5, 0.5)
+            .with_flip_x(true)
+            .with_flip_y(true)
+            .with_rotation(1.5);
+        assert_eq!(metadata.offset(), (1.0, -2.0));
+        assert_eq!(metadata.pivot(), Some((-0.5, 0.5)));
+        assert!(metadata.flip_x());
+        assert!(metadata.flip_y());
+        assert!((metadata.rotation() - 1.5).abs() < f32::EPSILON);
+        assert!(!metadata.is_default());
+        assert!(FrameMetadata::new().is_default());
+    }
+
+    #[test]
+    fn compares_exactly() {
+        assert_eq!(
+            FrameMetadata::new().with_rotation(f32::NAN),
+            FrameMetadata::new().with_rotation(f32::NAN)
+        );
+        assert_ne!(
+            FrameMetadata::new().with_offset(0.0, 0.0),
+            FrameMetadata::new().with_offset(-0.0, 0.0)
+        );
+    }
+}
@@ -1,10 +1,32 @@
-use core::time::Duration;
+use core::{
+    fmt::{self, Debug, Formatter},
+    num::NonZeroU32,
+    ops::{RangeBounds, RangeInclusive},
+    time::Duration,
+};
 
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
+#[cfg(feature = "aseprite")]
+pub use aseprite::AsepriteError;
+pub use atlas::{AtlasIndex, SpriteIndex};
+pub use metadata::FrameMetadata;
+pub use sample::Sample;
+pub use set::{AnimationSet, MissingAnimation};
+pub use validation::{Problem, Severity, Validation, ValidationReport};
+
+use crate::Error;
+
+#[cfg(feature = "aseprite")]
+mod aseprite;
+mod atlas;
 #[cfg(feature = "serde")]
 mod dto;
+mod metadata;
+mod sample;
+mod set;
+mod validation;
 
 /// Definition of an animation
 #[cfg_attr(
@@ -16,6 +38,9 @@ mod dto;
 ```yaml
 # The mode can be one of: 'Once', 'Repeat', 'PingPong'
 # or '!RepeatFrom: n' (where 'n' is the frame-index to repeat from)
+# or '!RepeatTimes: n', '!PingPongTimes: n' (where 'n' is the number of times to play)
+# or '!RepeatFromTimes { from: f, times: n }'
+# or '!Sustain { loop_start: s, loop_end: e }' (see 'Animation::sustain')
 # The default is 'Repeat'
 mode: PingPong
 frames:
@@ -25,6 +50,12 @@ frames:
     duration: 100
   - index: 2
     duration: 120
+    events: [footstep] # markers reported when the frame is entered
+  - index: 3
+    duration: 100
+    events: # markers may also be reported later in the frame
+      - name: spawn_projectile
+        offset: 50 # milliseconds since the beginning of the frame
 ```
 
 There is also a short-hand notation if all frames have the same duration:
@@ -33,40 +64,104 @@ There is also a short-hand notation if all frames have the same duration:
 fps: 12 # may be substitued by 'frame_duration' of 'total_duration'
 frames: [0, 1, 2] # sequence of frame indices
 ```
+
+The frames of an `Animation<T>` which values are not indices (see `Frame::from_value`) are written the same way,
+with the value of each frame in place of its index:
+
+```yaml
+fps: 12
+frames:
+  - [1, 3] # short-hand notation, here for a `T` deserialized from a sequence like `(u8, usize)`
+  - value: { atlas: 1, index: 4 } # a `T` deserialized from a map is written under `value` (or `index`)
+    duration: 100
+```
+
+The frames of an `Animation<AtlasIndex>` may also be written with their `atlas` along with their properties,
+like `{ atlas: boss_b, index: 3, duration: 100 }` (see `AtlasIndex`).
+
+Each frame may also define how its sprite is placed (see `FrameMetadata`):
+
+```yaml
+fps: 12
+frames:
+  - index: 0
+    offset: [2, -1] # draw offset, in world units
+    pivot: [0, -0.5] # pivot point, relative to the sprite size (here the bottom center)
+    flip_x: true
+    flip_y: false
+    rotation: 1.57 # in radians
+```
+
+For tick-based animations (see `Frame::from_ticks`), the durations may be expressed in ticks instead:
+
+```yaml
+tick_rate: 60 # number of ticks per second, required when using ticks
+frame_ticks: 3 # number of ticks of each frame
+frames:
+  - 0
+  - 1
+  - index: 2
+    ticks: 6 # overrides the number of ticks of this frame
+```
+
+An animation that ends may name the animation of its `AnimationSet` to play next (see `AnimationQueue`):
+
+```yaml
+mode: Once
+fps: 12
+frames: [10, 11, 12]
+next: recover
+```
+
+The deserialization fails with `Error::Invalid` if the validation (see `Animation::validate`) finds any error.
+The warnings are discarded: validate the deserialized animation to get them.
 "
 )]
-#[derive(Debug, Clone, Eq, PartialEq)]
+#[derive(Clone, Eq, PartialEq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(
     feature = "serde",
-    serde(try_from = "dto::AnimationDto", into = "dto::AnimationDto")
+    serde(
+        try_from = "dto::AnimationDto<T>",
+        into = "dto::AnimationDto<T>",
+        bound(
+            serialize = "T: Serialize + Clone",
+            deserialize = "T: Deserialize<'de>"
+        )
+    )
 )]
-pub struct Animation {
+pub struct Animation<T = usize> {
     /// Frames
-    pub(crate) frames: Vec<Frame>,
+    pub(crate) frames: Vec<Frame<T>>,
     /// Animation mode
     pub(crate) mode: Mode,
+    /// Named markers, sorted by position and offset
+    pub(crate) markers: Vec<Marker>,
+    /// Time at which each frame ends, relative to the beginning of the first frame
+    ///
+    /// Must be updated whenever the frames change
+    frame_ends: Vec<Duration>,
+    /// Name of the animation to play after this one ends
+    pub(crate) next: Option<String>,
 }
 
 /// A single animation frame
+///
+/// The value of the frame is what is displayed: by default the index in the sprite atlas,
+/// but it may be anything (see [`Frame::from_value`]).
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
-pub struct Frame {
-    /// Index in the sprite atlas
-    pub(crate) index: usize,
+pub struct Frame<T = usize> {
+    /// Value displayed, by default the index in the sprite atlas
+    pub(crate) value: T,
     /// How long should the frame be displayed
     pub(crate) duration: Duration,
+    /// How the sprite is placed
+    pub(crate) metadata: FrameMetadata,
+    /// Number of ticks the frame is displayed for, and their rate, if the frame is tick-based
+    pub(crate) ticks: Option<(NonZeroU32, TickRate)>,
 }
 
 impl Animation {
-    /// Create a new animation from frames
-    #[must_use]
-    pub fn from_frames(frames: impl IntoIterator<Item = Frame>) -> Self {
-        Self {
-            frames: frames.into_iter().collect(),
-            mode: Mode::default(),
-        }
-    }
-
     /// Create a new animation from an index iterator, using the same frame duration for each frame.
     ///
     /// # Examples
@@ -93,74 +188,627 @@ impl Animation {
     ///
     /// # Panics
     ///
-    /// Panics if the duration is zero
+    /// Panics if there is no index, or if the duration is zero
+    ///
+    /// See [`try_from_indices`](Self::try_from_indices) for a fallible version
     pub fn from_indices(indices: impl IntoIterator<Item = usize>, frame_rate: FrameRate) -> Self {
-        let mut anim: Self = indices
-            .into_iter()
-            .map(|index| Frame::new(index, frame_rate.frame_duration))
-            .collect();
+        Self::try_from_indices(indices, frame_rate).unwrap_or_else(|error| panic!("{error}"))
+    }
 
-        if frame_rate.is_total_duration {
-            #[allow(clippy::cast_precision_loss)]
-            let actual_duration = frame_rate.frame_duration.div_f64(anim.frames.len() as f64);
-            for frame in &mut anim.frames {
-                frame.duration = actual_duration;
-            }
-        }
+    /// Create a new animation from an index iterator, using the same frame duration for each frame.
+    ///
+    /// See [`from_indices`](Self::from_indices)
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NoFrame`] if there is no index,
+    /// and [`Error::ZeroDuration`] if the duration of the frames is zero
+    pub fn try_from_indices(
+        indices: impl IntoIterator<Item = usize>,
+        frame_rate: FrameRate,
+    ) -> Result<Self, Error> {
+        Self::try_from_values(indices, frame_rate)
+    }
+}
 
-        anim
+impl<T> Animation<T> {
+    /// Create a new animation from frames
+    #[must_use]
+    pub fn from_frames(frames: impl IntoIterator<Item = Frame<T>>) -> Self {
+        let mut animation = Self {
+            frames: frames.into_iter().collect(),
+            mode: Mode::default(),
+            markers: Vec::new(),
+            frame_ends: Vec::new(),
+            next: None,
+        };
+        animation.update_frame_ends();
+        animation
+    }
+
+    /// Create a new animation from an iterator of frame values, using the same frame duration for each frame.
+    ///
+    /// This is the generic version of [`from_indices`](Animation::from_indices),
+    /// for animations which frames are not indices in a sprite atlas.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use std::time::Duration;
+    /// use benimator::*;
+    ///
+    /// // Glyphs of a spinner, for a terminal game
+    /// let animation = Animation::from_values(['|', '/', '-', '\\'], FrameRate::from_fps(10.0));
+    /// let mut state = State::new();
+    ///
+    /// assert_eq!(state.update(&animation, Duration::from_millis(150)).value(), &'/');
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no value, or if the duration is zero
+    ///
+    /// See [`try_from_values`](Self::try_from_values) for a fallible version
+    pub fn from_values(values: impl IntoIterator<Item = T>, frame_rate: FrameRate) -> Self {
+        Self::try_from_values(values, frame_rate).unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    /// Create a new animation from an iterator of frame values, using the same frame duration for each frame.
+    ///
+    /// See [`from_values`](Self::from_values)
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NoFrame`] if there is no value,
+    /// and [`Error::ZeroDuration`] if the duration of the frames is zero
+    pub fn try_from_values(
+        values: impl IntoIterator<Item = T>,
+        frame_rate: FrameRate,
+    ) -> Result<Self, Error> {
+        let values: Vec<T> = values.into_iter().collect();
+        if values.is_empty() {
+            return Err(Error::NoFrame);
+        }
+        if let Some((ticks, tick_rate)) = frame_rate.ticks {
+            return Ok(values
+                .into_iter()
+                .map(|value| Frame::from_ticks(value, ticks, tick_rate))
+                .collect());
+        }
+        let duration = frame_rate.frame_duration_for(values.len());
+        values
+            .into_iter()
+            .map(|value| Frame::try_from_value(value, duration))
+            .collect()
     }
 
     /// Runs the animation once and then stop playing
     #[must_use]
-    pub fn once(mut self) -> Self {
-        self.mode = Mode::Once;
-        self
+    pub fn once(self) -> Self {
+        self.with_mode(Mode::Once)
     }
 
     /// Repeat the animation forever
     #[must_use]
-    pub fn repeat(mut self) -> Self {
-        self.mode = Mode::RepeatFrom(0);
-        self
+    pub fn repeat(self) -> Self {
+        self.with_mode(Mode::RepeatFrom(0))
     }
 
     /// Repeat the animation forever, from a given frame index (loop back to it at the end of the
     /// animation)
     #[must_use]
-    pub fn repeat_from(mut self, frame_index: usize) -> Self {
-        self.mode = Mode::RepeatFrom(frame_index);
-        self
+    pub fn repeat_from(self, frame_index: usize) -> Self {
+        self.with_mode(Mode::RepeatFrom(frame_index))
     }
 
     /// Repeat the animation forever, going back and forth between the first and last frame.
     #[must_use]
-    pub fn ping_pong(mut self) -> Self {
-        self.mode = Mode::PingPong;
+    pub fn ping_pong(self) -> Self {
+        self.with_mode(Mode::PingPong)
+    }
+
+    /// Play the animation `times` times, and then stop playing
+    #[must_use]
+    pub fn repeat_times(self, times: NonZeroU32) -> Self {
+        self.repeat_from_times(0, times)
+    }
+
+    /// Play the animation `times` times, looping back to the given frame index at the end of each
+    /// pass but the last, and then stop playing
+    #[must_use]
+    pub fn repeat_from_times(self, frame_index: usize, times: NonZeroU32) -> Self {
+        self.with_mode(Mode::RepeatTimes {
+            from: frame_index,
+            times,
+        })
+    }
+
+    /// Go back and forth between the first and last frame, and stop playing after `times` sweeps
+    ///
+    /// Each sweep from one end to the other counts once.
+    /// So the animation ends on the last frame if `times` is odd, and on the first frame if it is even.
+    #[must_use]
+    pub fn ping_pong_times(self, times: NonZeroU32) -> Self {
+        self.with_mode(Mode::PingPongTimes(times))
+    }
+
+    /// Play the frames before `loop_frames` once (intro), then repeat `loop_frames` as long as the
+    /// [`State`](crate::State) is held, and finally play the frames after `loop_frames` once (outro)
+    /// after the state is [released](crate::State::release).
+    ///
+    /// When released, the current pass of the loop is completed before playing the outro.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use std::time::Duration;
+    /// use benimator::*;
+    ///
+    /// // Frame 0 is the intro, frames 1 and 2 are looped, and frame 3 is the outro
+    /// let animation = Animation::from_indices(0..=3, FrameRate::from_fps(10.0)).sustain(1..=2);
+    /// let mut state = State::new();
+    ///
+    /// state.update(&animation, Duration::from_millis(1000));
+    /// assert!(state.frame_index() == 1 || state.frame_index() == 2);
+    ///
+    /// state.release();
+    /// state.update(&animation, Duration::from_millis(1000));
+    /// assert_eq!(state.frame_index(), 3);
+    /// assert!(state.is_ended());
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `loop_frames` is empty
+    ///
+    /// See [`try_sustain`](Self::try_sustain) for a fallible version
+    #[must_use]
+    pub fn sustain(self, loop_frames: RangeInclusive<usize>) -> Self {
+        self.try_sustain(loop_frames)
+            .unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    /// Play the frames before `loop_frames`, then repeat `loop_frames` until the state is released
+    ///
+    /// See [`sustain`](Self::sustain)
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::EmptyLoop`] if `loop_frames` is empty
+    pub fn try_sustain(self, loop_frames: RangeInclusive<usize>) -> Result<Self, Error> {
+        let (loop_start, loop_end) = (*loop_frames.start(), *loop_frames.end());
+        if loop_frames.is_empty() {
+            return Err(Error::EmptyLoop {
+                loop_start,
+                loop_end,
+            });
+        }
+        self.try_with_mode(Mode::Sustain {
+            loop_start,
+            loop_end,
+        })
+    }
+
+    /// Set the animation mode
+    ///
+    /// See [`set_mode`](Self::set_mode)
+    ///
+    /// # Panics
+    ///
+    /// Panics if the mode is a [`Mode::Sustain`] with an empty loop
+    ///
+    /// See [`try_with_mode`](Self::try_with_mode) for a fallible version
+    #[must_use]
+    pub fn with_mode(mut self, mode: Mode) -> Self {
+        self.set_mode(mode);
         self
     }
 
+    /// Set the animation mode
+    ///
+    /// See [`set_mode`](Self::set_mode)
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::EmptyLoop`] if the mode is a [`Mode::Sustain`] with an empty loop
+    pub fn try_with_mode(mut self, mode: Mode) -> Result<Self, Error> {
+        mode.validate()?;
+        self.mode = mode;
+        Ok(self)
+    }
+
+    /// Attach a named marker to the beginning of the frame at the given position
+    ///
+    /// The marker is reported by [`State::update_with_events`](crate::State::update_with_events)
+    /// when the frame is entered.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use std::time::Duration;
+    /// use benimator::*;
+    ///
+    /// let animation = Animation::from_indices(0..=3, FrameRate::from_fps(10.0))
+    ///     .with_marker(1, "footstep")
+    ///     .with_marker(3, "footstep");
+    ///
+    /// let mut state = State::new();
+    /// let mut events = Vec::new();
+    /// state.update_with_events(&animation, Duration::from_millis(150), &mut events);
+    ///
+    /// assert!(events
+    ///     .iter()
+    ///     .any(|event| matches!(event, Event::Marker { name: "footstep", .. })));
+    /// ```
+    #[must_use]
+    pub fn with_marker(self, position: usize, name: impl Into<String>) -> Self {
+        self.with_marker_at(position, Duration::ZERO, name)
+    }
+
+    /// Attach a named marker to the frame at the given position, `offset` after the beginning of the frame
+    ///
+    /// The marker is reported by [`State::update_with_events`](crate::State::update_with_events)
+    /// when the time elapsed in the frame reaches `offset`.
+    /// A marker with an offset greater or equal to the frame duration is never reported,
+    /// and is found by the [validation](Self::validate) like a marker on a frame that does not exist.
+    #[must_use]
+    pub fn with_marker_at(
+        mut self,
+        position: usize,
+        offset: Duration,
+        name: impl Into<String>,
+    ) -> Self {
+        let index = self
+            .markers
+            .partition_point(|m| (m.position, m.offset) <= (position, offset));
+        self.markers.insert(
+            index,
+            Marker {
+                position,
+                offset,
+                name: name.into(),
+            },
+        );
+        self
+    }
+
+    /// Set the name of the animation to play after this one ends
+    ///
+    /// The name refers to another animation of the same [`AnimationSet`],
+    /// and is followed by an [`AnimationQueue`](crate::AnimationQueue) when nothing else is queued.
+    /// It has no effect on an animation that never ends.
+    #[must_use]
+    pub fn with_next(mut self, name: impl Into<String>) -> Self {
+        self.next = Some(name.into());
+        self
+    }
+
+    /// Returns the name of the animation to play after this one ends, if any
+    ///
+    /// See [`with_next`](Self::with_next)
+    #[must_use]
+    pub fn next(&self) -> Option<&str> {
+        self.next.as_deref()
+    }
+
+    /// Returns the names of the markers of the frame at `position` which offset is in the `offsets` range
+    pub(crate) fn markers(
+        &self,
+        position: usize,
+        offsets: impl RangeBounds<Duration>,
+    ) -> impl Iterator<Item = &str> {
+        let start = self.markers.partition_point(|m| m.position < position);
+        let end = self.markers.partition_point(|m| m.position <= position);
+        self.markers[start..end]
+            .iter()
+            .filter(move |m| offsets.contains(&m.offset))
+            .map(|m| m.name.as_str())
+    }
+
+    /// Returns the frames of the animation
+    #[must_use]
+    pub fn frames(&self) -> &[Frame<T>] {
+        &self.frames
+    }
+
+    /// Returns the number of frames
+    #[must_use]
+    pub fn frame_count(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Returns the animation mode
+    #[must_use]
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    /// Set the animation mode
+    ///
+    /// # Panics
+    ///
+    /// Panics if the mode is a [`Mode::Sustain`] with an empty loop
+    pub fn set_mode(&mut self, mode: Mode) {
+        if let Err(error) = mode.validate() {
+            panic!("{error}");
+        }
+        self.mode = mode;
+    }
+
+    /// Returns the sum of the frame durations
+    #[must_use]
+    pub fn duration(&self) -> Duration {
+        self.frame_ends.last().copied().unwrap_or_default()
+    }
+
+    /// Returns the duration of the part of the animation that repeats
+    ///
+    /// * For a repeating animation, it is the duration of the frames from the one it repeats from to the last one
+    /// * For a ping-pong animation, it is the duration of a backward and a forward sweep
+    ///   (where the first and last frames are played only once)
+    /// * For a [`sustain`](Self::sustain) animation, it is the duration of the loop
+    /// * For an animation played [`once`](Self::once), it is the total duration
+    ///
+    /// The same is true for the animations played a finite number of times.
+    #[must_use]
+    pub fn cycle_duration(&self) -> Duration {
+        let Some(last) = self.frames.len().checked_sub(1) else {
+            return Duration::ZERO;
+        };
+        match self.clamped_mode() {
+            Mode::Once => self.duration(),
+            Mode::RepeatFrom(from) | Mode::RepeatTimes { from, .. } => {
+                self.loop_duration(from, last)
+            }
+            Mode::PingPong | Mode::PingPongTimes(_) if last == 0 => self.duration(),
+            Mode::PingPong | Mode::PingPongTimes(_) => self.ping_pong_cycle(),
+            Mode::Sustain {
+                loop_start,
+                loop_end,
+            } => self.loop_duration(loop_start, loop_end),
+        }
+    }
+
+    /// Insert a frame at `position`, shifting all frames after it
+    ///
+    /// The markers and the frame positions of the [`Mode`] follow the frames they refer to.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `position` is greater than the number of frames
+    pub fn insert_frame(&mut self, position: usize, frame: Frame<T>) {
+        self.frames.insert(position, frame);
+        let shift = |p: &mut usize| {
+            if *p >= position {
+                *p += 1;
+            }
+        };
+        self.markers.iter_mut().for_each(|m| shift(&mut m.position));
+        self.mode.shift_positions(shift);
+        self.update_frame_ends();
+    }
+
+    /// Remove and return the frame at `position`, shifting all frames after it
+    ///
+    /// The markers of the removed frame are removed as well.
+    /// The markers and the frame positions of the [`Mode`] follow the frames they refer to.
+    /// If the mode refers to the removed frame, it refers to the next one instead (or the last one if there is none).
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no frame at `position`, or if it is the only frame of the animation
+    pub fn remove_frame(&mut self, position: usize) -> Frame<T> {
+        assert!(
+            self.frames.len() > 1 || position > 0,
+            "The only frame of an animation cannot be removed"
+        );
+        let frame = self.frames.remove(position);
+        self.markers.retain(|m| m.position != position);
+        let last = self.frames.len().saturating_sub(1);
+        let shift = |p: &mut usize| {
+            if *p > position {
+                *p -= 1;
+            }
+            *p = (*p).min(last);
+        };
+        self.markers.iter_mut().for_each(|m| shift(&mut m.position));
+        self.mode.shift_positions(shift);
+        self.update_frame_ends();
+        frame
+    }
+
+    /// Replace the frame at `position`, and return the previous one
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no frame at `position`
+    pub fn replace_frame(&mut self, position: usize, frame: Frame<T>) -> Frame<T> {
+        let previous = core::mem::replace(&mut self.frames[position], frame);
+        self.update_frame_ends();
+        previous
+    }
+
+    /// Set the duration of the frame at `position`
+    ///
+    /// The frame is no longer [tick-based](Frame::ticks).
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no frame at `position`, or if the duration is zero
+    pub fn set_frame_duration(&mut self, position: usize, duration: Duration) {
+        if let Err(error) = Frame::<T>::validate_duration(duration) {
+            panic!("{error}");
+        }
+        self.frames[position].duration = duration;
+        self.frames[position].ticks = None;
+        self.update_frame_ends();
+    }
+
+    /// Returns the time at which the frame at `position` starts, relative to the first frame
+    /// Returns the mode, with the frame positions it refers to clamped to the last frame
+    ///
+    /// The frame positions of a mode may not exist (like after [`set_mode`](Self::set_mode)),
+    /// and are clamped the same way by the [`State`](crate::State) and by [`frame_at`](Self::frame_at).
+    pub(crate) fn clamped_mode(&self) -> Mode {
+        let last = self.frames.len().saturating_sub(1);
+        let mut mode = self.mode;
+        mode.shift_positions(|position| *position = (*position).min(last));
+        mode
+    }
+
+    pub(crate) fn frame_start(&self, position: usize) -> Duration {
+        match position {
+            0 => Duration::ZERO,
+            _ => self.frame_ends[position - 1],
+        }
+    }
+
+    fn update_frame_ends(&mut self) {
+        let mut end = Duration::ZERO;
+        self.frame_ends.clear();
+        self.frame_ends.extend(self.frames.iter().map(|frame| {
+            end += frame.duration;
+            end
+        }));
+    }
+
+    /// Returns the time of the markers that are reached when playing the animation, with their names
+    ///
+    /// The times are relative to the beginning of the first frame, in increasing order.
+    pub(crate) fn marker_times(&self) -> impl Iterator<Item = (Duration, &str)> {
+        self.markers
+            .iter()
+            .filter(|m| {
+                self.frames
+                    .get(m.position)
+                    .is_some_and(|frame| m.offset < frame.duration)
+            })
+            .map(|m| (self.frame_start(m.position) + m.offset, m.name.as_str()))
+    }
+
     pub(crate) fn has_frames(&self) -> bool {
         !self.frames.is_empty()
     }
 }
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
-pub(crate) enum Mode {
+/// A named point in time of an animation frame
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(crate) struct Marker {
+    /// Position of the frame in the animation
+    pub(crate) position: usize,
+    /// Time since the beginning of the frame
+    pub(crate) offset: Duration,
+    pub(crate) name: String,
+}
+
+/// How an animation is played
+///
+/// The frame positions are the positions of frames in the animation (not their indices in the sprite atlas).
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub enum Mode {
+    /// Play the animation once, and then stop playing
+    ///
+    /// See [`Animation::once`]
     Once,
+    /// Repeat the animation forever, looping back to the frame at the given position
+    ///
+    /// See [`Animation::repeat_from`]
     RepeatFrom(usize),
+    /// Go back and forth between the first and last frame forever
+    ///
+    /// See [`Animation::ping_pong`]
     PingPong,
+    /// Play the animation `times` times, looping back to the frame at position `from`
+    ///
+    /// See [`Animation::repeat_from_times`]
+    RepeatTimes {
+        /// Position of the frame to loop back to
+        from: usize,
+        /// Number of times the animation is played
+        times: NonZeroU32,
+    },
+    /// Go back and forth between the first and last frame, and stop after the given number of sweeps
+    ///
+    /// See [`Animation::ping_pong_times`]
+    PingPongTimes(NonZeroU32),
+    /// Play an intro, repeat a loop while the state is held, and then play an outro
+    ///
+    /// `loop_start` must not be greater than `loop_end`.
+    ///
+    /// See [`Animation::sustain`]
+    Sustain {
+        /// Position of the first frame of the loop
+        loop_start: usize,
+        /// Position of the last frame of the loop
+        loop_end: usize,
+    },
+}
+
+impl Mode {
+    fn validate(self) -> Result<(), Error> {
+        match self {
+            Mode::Sustain {
+                loop_start,
+                loop_end,
+            } if loop_start > loop_end => Err(Error::EmptyLoop {
+                loop_start,
+                loop_end,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns the frame positions the mode refers to
+    fn positions(self) -> impl Iterator<Item = usize> {
+        let (first, second) = match self {
+            Mode::RepeatFrom(from) | Mode::RepeatTimes { from, .. } => (Some(from), None),
+            Mode::Sustain {
+                loop_start,
+                loop_end,
+            } => (Some(loop_start), Some(loop_end)),
+            Mode::Once | Mode::PingPong | Mode::PingPongTimes(_) => (None, None),
+        };
+        first.into_iter().chain(second)
+    }
+
+    /// Apply `shift` to the frame positions of the mode
+    fn shift_positions(&mut self, mut shift: impl FnMut(&mut usize)) {
+        match self {
+            Mode::RepeatFrom(from) | Mode::RepeatTimes { from, .. } => shift(from),
+            Mode::Sustain {
+                loop_start,
+                loop_end,
+            } => {
+                shift(loop_start);
+                shift(loop_end);
+            }
+            Mode::Once | Mode::PingPong | Mode::PingPongTimes(_) => (),
+        }
+    }
 }
 
-impl FromIterator<Frame> for Animation {
-    fn from_iter<T: IntoIterator<Item = Frame>>(iter: T) -> Self {
+impl<T> FromIterator<Frame<T>> for Animation<T> {
+    fn from_iter<I: IntoIterator<Item = Frame<T>>>(iter: I) -> Self {
         Self::from_frames(iter)
     }
 }
 
-impl Extend<Frame> for Animation {
-    fn extend<T: IntoIterator<Item = Frame>>(&mut self, iter: T) {
+impl<T> Extend<Frame<T>> for Animation<T> {
+    fn extend<I: IntoIterator<Item = Frame<T>>>(&mut self, iter: I) {
         self.frames.extend(iter);
+        self.update_frame_ends();
+    }
+}
+
+impl<T: Debug> Debug for Animation<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Animation")
+            .field("frames", &self.frames)
+            .field("mode", &self.mode)
+            .field("markers", &self.markers)
+            .field("next", &self.next)
+            .finish_non_exhaustive()
     }
 }
 
@@ -179,14 +827,125 @@ impl Frame {
     /// # Panics
     ///
     /// Panics if the duration is zero
+    ///
+    /// See [`try_new`](Self::try_new) for a fallible version
     #[inline]
     #[must_use]
     pub fn new(index: usize, duration: Duration) -> Self {
-        assert!(
-            !duration.is_zero(),
-            "zero-duration is invalid for animation frame"
-        );
-        Self { index, duration }
+        Self::from_value(index, duration)
+    }
+
+    /// Create a new animation frame
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::ZeroDuration`] if the duration is zero
+    #[inline]
+    pub fn try_new(index: usize, duration: Duration) -> Result<Self, Error> {
+        Self::try_from_value(index, duration)
+    }
+
+    /// Returns the index in the sprite atlas
+    #[inline]
+    #[must_use]
+    pub fn index(&self) -> usize {
+        self.value
+    }
+}
+
+impl<T> Frame<T> {
+    /// Create a new animation frame displaying any value
+    ///
+    /// The value may be an index in a sprite atlas, a pair of atlas and index, an image handle,
+    /// a character for a terminal game, or a struct with the index and flip flags...
+    ///
+    /// # Panics
+    ///
+    /// Panics if the duration is zero
+    ///
+    /// See [`try_from_value`](Self::try_from_value) for a fallible version
+    #[inline]
+    #[must_use]
+    pub fn from_value(value: T, duration: Duration) -> Self {
+        Self::try_from_value(value, duration).unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    /// Create a new animation frame displaying any value
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::ZeroDuration`] if the duration is zero
+    #[inline]
+    pub fn try_from_value(value: T, duration: Duration) -> Result<Self, Error> {
+        Self::validate_duration(duration)?;
+        Ok(Self {
+            value,
+            duration,
+            metadata: FrameMetadata::default(),
+            ticks: None,
+        })
+    }
+
+    /// Create a new animation frame, displayed for the given number of ticks
+    ///
+    /// Ticks are integer time units, for games that update their animations at a fixed timestep
+    /// with [`State::update_ticks`](crate::State::update_ticks).
+    /// The duration of the frame is `ticks` times the [duration of a tick](TickRate::tick_duration),
+    /// so that no floating-point arithmetic is involved,
+    /// and updating an animation made of tick-based frames by ticks gives the same results on every machine.
+    #[inline]
+    #[must_use]
+    pub fn from_ticks(value: T, ticks: NonZeroU32, tick_rate: TickRate) -> Self {
+        Self {
+            value,
+            duration: tick_rate.duration(ticks.get()),
+            metadata: FrameMetadata::default(),
+            ticks: Some((ticks, tick_rate)),
+        }
+    }
+
+    /// Set how the sprite of the frame is placed (offset, pivot, flip and rotation)
+    #[inline]
+    #[must_use]
+    pub fn with_metadata(mut self, metadata: FrameMetadata) -> Self {
+        self.metadata = metadata;
+        self
+    }
+
+    fn validate_duration(duration: Duration) -> Result<(), Error> {
+        if duration.is_zero() {
+            return Err(Error::ZeroDuration);
+        }
+        Ok(())
+    }
+
+    /// Returns the value displayed by the frame
+    #[inline]
+    #[must_use]
+    pub fn value(&self) -> &T {
+        &self.value
+    }
+
+    /// Returns how long the frame is displayed
+    #[inline]
+    #[must_use]
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    /// Returns how the sprite of the frame is placed
+    #[inline]
+    pub fn metadata(&self) -> &FrameMetadata {
+        &self.metadata
+    }
+
+    /// Returns the number of ticks the frame is displayed for, and their rate, if the frame is tick-based
+    ///
+    /// See [`from_ticks`](Self::from_ticks)
+    #[inline]
+    #[must_use]
+    pub fn ticks(&self) -> Option<(NonZeroU32, TickRate)> {
+        self.ticks
     }
 }
 
@@ -196,6 +955,79 @@ impl Frame {
 pub struct FrameRate {
     frame_duration: Duration,
     is_total_duration: bool,
+    /// Number of ticks of each frame, and their rate, for tick-based frames
+    ticks: Option<(NonZeroU32, TickRate)>,
+}
+
+/// Number of ticks per second of a fixed-timestep game loop
+///
+/// It defines the duration of a tick, for the tick-based frames (see [`Frame::from_ticks`])
+/// and the updates by ticks (see [`State::update_ticks`](crate::State::update_ticks)).
+///
+/// # Example
+///
+/// ```
+/// # use std::num::NonZeroU32;
+/// # use std::time::Duration;
+/// use benimator::TickRate;
+///
+/// let tick_rate = TickRate::from_hz(NonZeroU32::new(50).unwrap());
+/// assert_eq!(tick_rate.tick_duration(), Duration::from_millis(20));
+/// assert_eq!(tick_rate.duration(3), Duration::from_millis(60));
+/// ```
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct TickRate(NonZeroU32);
+
+impl TickRate {
+    /// Tick rate defined by the number of ticks per second
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are more than a billion ticks per second, since a tick would be shorter than a nanosecond
+    ///
+    /// See [`try_from_hz`](Self::try_from_hz) for a fallible version
+    #[must_use]
+    pub fn from_hz(hz: NonZeroU32) -> Self {
+        Self::try_from_hz(hz).unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    /// Tick rate defined by the number of ticks per second
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidTickRate`] if there are more than a billion ticks per second
+    pub fn try_from_hz(hz: NonZeroU32) -> Result<Self, Error> {
+        if hz.get() > 1_000_000_000 {
+            return Err(Error::InvalidTickRate(hz.get()));
+        }
+        Ok(Self(hz))
+    }
+
+    /// Returns the number of ticks per second
+    #[inline]
+    #[must_use]
+    pub fn hz(self) -> NonZeroU32 {
+        self.0
+    }
+
+    /// Returns the duration of a tick
+    ///
+    /// It is one second divided by the number of ticks per second, truncated to the nanosecond.
+    #[inline]
+    #[must_use]
+    pub fn tick_duration(self) -> Duration {
+        Duration::from_secs(1) / self.0.get()
+    }
+
+    /// Returns the duration of `ticks` ticks
+    ///
+    /// It is always `ticks` times the [duration of a tick](Self::tick_duration),
+    /// so that updating by one tick at a time or by many ticks at once gives the same result.
+    #[inline]
+    #[must_use]
+    pub fn duration(self, ticks: u32) -> Duration {
+        self.tick_duration() * ticks
+    }
 }
 
 impl FrameRate {
@@ -204,12 +1036,26 @@ impl FrameRate {
     /// # Panics
     ///
     /// This function will panic if `fps` is negative, zero or not finite.
+    ///
+    /// See [`try_from_fps`](Self::try_from_fps) for a fallible version
     pub fn from_fps(fps: f64) -> Self {
-        assert!(fps.is_finite() && fps > 0.0, "Invalid FPS: ${fps}");
-        Self {
+        Self::try_from_fps(fps).unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    /// Frame rate defined by the FPS (Frame-Per-Second)
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidFps`] if `fps` is negative, zero or not finite.
+    pub fn try_from_fps(fps: f64) -> Result<Self, Error> {
+        if !fps.is_finite() || fps <= 0.0 {
+            return Err(Error::InvalidFps(fps));
+        }
+        Ok(Self {
             frame_duration: Duration::from_secs(1).div_f64(fps),
             is_total_duration: false,
-        }
+            ticks: None,
+        })
     }
 
     /// Frame rate defined by the duration of each frame
@@ -217,6 +1063,7 @@ impl FrameRate {
         Self {
             frame_duration: duration,
             is_total_duration: false,
+            ticks: None,
         }
     }
 
@@ -227,6 +1074,51 @@ impl FrameRate {
         Self {
             frame_duration: duration,
             is_total_duration: true,
+            ticks: None,
+        }
+    }
+
+    /// Frame rate defined by the number of ticks each frame is displayed for
+    ///
+    /// The frames are tick-based, see [`Frame::from_ticks`]
+    pub fn from_ticks(ticks: NonZeroU32, tick_rate: TickRate) -> Self {
+        Self {
+            ticks: Some((ticks, tick_rate)),
+            ..Self::from_frame_duration(tick_rate.duration(ticks.get()))
+        }
+    }
+
+    /// Frame rate defined by the duration of each frame
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::ZeroDuration`] if the duration is zero
+    pub fn try_from_frame_duration(duration: Duration) -> Result<Self, Error> {
+        if duration.is_zero() {
+            return Err(Error::ZeroDuration);
+        }
+        Ok(Self::from_frame_duration(duration))
+    }
+
+    /// Frame rate defined by the total duration of the animation
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::ZeroDuration`] if the duration is zero
+    pub fn try_from_total_duration(duration: Duration) -> Result<Self, Error> {
+        if duration.is_zero() {
+            return Err(Error::ZeroDuration);
+        }
+        Ok(Self::from_total_duration(duration))
+    }
+
+    /// Returns the duration of each frame, for an animation of `frame_count` frames
+    pub(crate) fn frame_duration_for(self, frame_count: usize) -> Duration {
+        if self.is_total_duration {
+            #[allow(clippy::cast_precision_loss)]
+            self.frame_duration.div_f64(frame_count.max(1) as f64)
+        } else {
+            self.frame_duration
         }
     }
 }
@@ -264,6 +1156,23 @@ mod tests {
         );
     }
 
+    #[test]
+    fn from_values() {
+        let animation = Animation::from_values(['a', 'b'], FrameRate::from_fps(10.0));
+        assert_eq!(
+            animation,
+            Animation::from_frames([
+                Frame::from_value('a', Duration::from_millis(100)),
+                Frame::from_value('b', Duration::from_millis(100)),
+            ])
+        );
+        assert_eq!(animation.frames()[1].value(), &'b');
+        assert_eq!(
+            Animation::try_from_values(['a'], FrameRate::from_frame_duration(Duration::ZERO)),
+            Err(Error::ZeroDuration)
+        );
+    }
+
     #[test]
     fn fps_frame_duration_equivalence() {
         assert_eq!(
@@ -288,4 +1197,182 @@ mod tests {
             ),
         );
     }
+
+    #[fixture]
+    fn animation() -> Animation {
+        Animation::from_indices(
+            10..=13,
+            FrameRate::from_frame_duration(Duration::from_secs(1)),
+        )
+    }
+
+    #[rstest]
+    fn accessors(animation: Animation) {
+        assert_eq!(animation.frame_count(), 4);
+        assert_eq!(animation.frames()[1].index(), 11);
+        assert_eq!(animation.frames()[1].duration(), Duration::from_secs(1));
+        assert_eq!(animation.duration(), Duration::from_secs(4));
+        assert_eq!(animation.mode(), Mode::RepeatFrom(0));
+    }
+
+    #[rstest]
+    #[case(Mode::Once, 4)]
+    #[case(Mode::RepeatFrom(0), 4)]
+    #[case(Mode::RepeatFrom(1), 3)]
+    #[case(Mode::RepeatTimes { from: 3, times: NonZeroU32::new(2).unwrap() }, 1)]
+    #[case(Mode::PingPong, 6)]
+    #[case(Mode::PingPongTimes(NonZeroU32::new(2).unwrap()), 6)]
+    #[case(Mode::Sustain { loop_start: 1, loop_end: 2 }, 2)]
+    fn cycle_duration(animation: Animation, #[case] mode: Mode, #[case] expected_secs: u64) {
+        assert_eq!(
+            animation.with_mode(mode).cycle_duration(),
+            Duration::from_secs(expected_secs)
+        );
+    }
+
+    #[rstest]
+    fn insert_frame(animation: Animation) {
+        let mut animation = animation.sustain(1..=2).with_marker(1, "a");
+        animation.insert_frame(1, Frame::new(0, Duration::from_secs(2)));
+        assert_eq!(animation.frame_count(), 5);
+        assert_eq!(animation.frames()[1].index(), 0);
+        assert_eq!(animation.frames()[2].index(), 11);
+        assert_eq!(animation.duration(), Duration::from_secs(6));
+        assert_eq!(
+            animation.mode(),
+            Mode::Sustain {
+                loop_start: 2,
+                loop_end: 3
+            }
+        );
+        assert_eq!(animation.markers(2, ..).collect::<Vec<_>>(), vec!["a"]);
+    }
+
+    #[rstest]
+    fn remove_frame(animation: Animation) {
+        let mut animation = animation
+            .repeat_from(3)
+            .with_marker(1, "a")
+            .with_marker(2, "b");
+        let removed = animation.remove_frame(1);
+        assert_eq!(removed.index(), 11);
+        assert_eq!(animation.frame_count(), 3);
+        assert_eq!(animation.duration(), Duration::from_secs(3));
+        assert_eq!(animation.mode(), Mode::RepeatFrom(2));
+        assert_eq!(animation.markers.len(), 1);
+        assert_eq!(animation.markers(1, ..).collect::<Vec<_>>(), vec!["b"]);
+    }
+
+    #[test]
+    #[should_panic(expected = "only frame")]
+    fn remove_only_frame() {
+        let mut animation = Animation::from_indices([0], FrameRate::from_fps(10.0));
+        animation.remove_frame(0);
+    }
+
+    #[rstest]
+    fn remove_last_frame_of_loop(animation: Animation) {
+        let mut animation = animation.repeat_from(3);
+        animation.remove_frame(3);
+        assert_eq!(animation.mode(), Mode::RepeatFrom(2));
+    }
+
+    #[rstest]
+    fn set_frame_duration(animation: Animation) {
+        let mut animation = animation;
+        animation.set_frame_duration(0, Duration::from_secs(3));
+        assert_eq!(
+            animation.frames()[0],
+            Frame::new(10, Duration::from_secs(3))
+        );
+        assert_eq!(animation.duration(), Duration::from_secs(6));
+        assert_eq!(animation.frame_at(Duration::from_secs(3)).index(), Some(11));
+    }
+
+    #[rstest]
+    fn replace_frame(animation: Animation) {
+        let mut animation = animation;
+        let previous = animation.replace_frame(3, Frame::new(42, Duration::from_secs(2)));
+        assert_eq!(previous.index(), 13);
+        assert_eq!(animation.duration(), Duration::from_secs(5));
+    }
+
+    #[rstest]
+    #[should_panic(expected = "zero-duration")]
+    fn set_zero_frame_duration(animation: Animation) {
+        let mut animation = animation;
+        animation.set_frame_duration(0, Duration::ZERO);
+    }
+
+    #[rstest]
+    #[should_panic(expected = "invalid loop")]
+    fn set_empty_sustain_loop(animation: Animation) {
+        let mut animation = animation;
+        animation.set_mode(Mode::Sustain {
+            loop_start: 2,
+            loop_end: 1,
+        });
+    }
+
+    #[rstest]
+    fn try_from_fps_invalid(#[values(0.0, -1.0, f64::NAN, f64::INFINITY)] fps: f64) {
+        assert!(matches!(
+            FrameRate::try_from_fps(fps),
+            Err(Error::InvalidFps(_))
+        ));
+    }
+
+    #[test]
+    fn try_new_zero_duration() {
+        assert_eq!(Frame::try_new(0, Duration::ZERO), Err(Error::ZeroDuration));
+        assert_eq!(
+            FrameRate::try_from_frame_duration(Duration::ZERO),
+            Err(Error::ZeroDuration)
+        );
+        assert_eq!(
+            FrameRate::try_from_total_duration(Duration::ZERO),
+            Err(Error::ZeroDuration)
+        );
+    }
+
+    #[test]
+    fn try_from_indices_too_short_total_duration() {
+        assert_eq!(
+            Animation::try_from_indices(
+                0..=2,
+                FrameRate::from_total_duration(Duration::from_nanos(1))
+            ),
+            Err(Error::ZeroDuration)
+        );
+    }
+
+    #[rstest]
+    #[case::fps(FrameRate::from_fps(10.0))]
+    #[case::total_duration(FrameRate::from_total_duration(Duration::from_secs(1)))]
+    #[case::ticks(FrameRate::from_ticks(NonZeroU32::new(3).unwrap(), TickRate::from_hz(NonZeroU32::new(60).unwrap())))]
+    fn try_from_indices_without_frame(#[case] frame_rate: FrameRate) {
+        assert_eq!(
+            Animation::try_from_indices([], frame_rate),
+            Err(Error::NoFrame)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "no frame")]
+    fn from_indices_without_frame() {
+        let _ = Animation::from_indices([], FrameRate::from_total_duration(Duration::from_secs(1)));
+    }
+
+    #[rstest]
+    fn try_sustain_empty_loop(animation: Animation) {
+        #[allow(clippy::reversed_empty_ranges)]
+        let result = animation.try_sustain(2..=1);
+        assert_eq!(
+            result,
+            Err(Error::EmptyLoop {
+                loop_start: 2,
+                loop_end: 1
+            })
+        );
+    }
 }
@@ -0,0 +1,436 @@
+use core::time::Duration;
+
+use super::{Animation, Mode, SpriteIndex};
+
+/// Frame of an animation at a given point in time
+///
+/// See [`Animation::frame_at`]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Sample {
+    pub(crate) index: Option<usize>,
+    pub(crate) position: usize,
+    pub(crate) elapsed_in_frame: Duration,
+    pub(crate) going_backward: bool,
+    pub(crate) is_ended: bool,
+    /// Number of loops completed
+    pub(crate) loops: u32,
+}
+
+impl Sample {
+    /// Returns the index of the frame in the sprite atlas
+    ///
+    /// For an [`AtlasIndex`](crate::AtlasIndex) value, it is the index in its atlas.
+    /// It is `None` if the value of the frame has no [sprite index](SpriteIndex::sprite_index).
+    #[must_use]
+    pub fn index(&self) -> Option<usize> {
+        self.index
+    }
+
+    /// Returns the position of the frame in the animation
+    #[must_use]
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Returns the time elapsed since the frame was entered
+    #[must_use]
+    pub fn elapsed_in_frame(&self) -> Duration {
+        self.elapsed_in_frame
+    }
+
+    /// Returns true if the animation has ended
+    #[must_use]
+    pub fn is_ended(&self) -> bool {
+        self.is_ended
+    }
+}
+
+impl<T: SpriteIndex> Animation<T> {
+    /// Returns the frame displayed after playing the animation for `elapsed` time from the beginning
+    ///
+    /// This gives the same result as updating a new [`State`](crate::State) with the same total elapsed time,
+    /// without the need to keep a mutable state.
+    /// The cost does not depend on `elapsed`, and is logarithmic in the number of frames.
+    ///
+    /// A [`sustain`](Self::sustain) animation is sampled as if the state was never released.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use std::time::Duration;
+    /// use benimator::*;
+    ///
+    /// let animation = Animation::from_indices(0..=3, FrameRate::from_fps(10.0)).ping_pong();
+    ///
+    /// assert_eq!(animation.frame_at(Duration::from_millis(250)).index(), Some(2));
+    /// assert_eq!(animation.frame_at(Duration::from_millis(450)).index(), Some(2));
+    /// assert_eq!(animation.frame_at(Duration::from_secs(3600)).index(), Some(0));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the animation has no frame
+    #[must_use]
+    pub fn frame_at(&self, elapsed: Duration) -> Sample {
+        assert!(self.has_frames(), "the animation has no frame");
+        let last = self.frames.len() - 1;
+        if self.end_time().is_some_and(|end| elapsed >= end) {
+            return self.sample_end();
+        }
+        match self.clamped_mode() {
+            Mode::Once => self.sample_forward(elapsed),
+            Mode::RepeatFrom(from) | Mode::RepeatTimes { from, .. } => {
+                self.sample_loop(elapsed, from, last)
+            }
+            Mode::Sustain {
+                loop_start,
+                loop_end,
+            } => self.sample_loop(elapsed, loop_start, loop_end),
+            Mode::PingPong | Mode::PingPongTimes(_) => self.sample_ping_pong(elapsed),
+        }
+    }
+}
+
+impl<T> Animation<T> {
+    /// Returns the time at which the animation ends, if it ever does
+    ///
+    /// A [`sustain`](Self::sustain) animation never ends as long as the state is held.
+    pub(crate) fn end_time(&self) -> Option<Duration> {
+        let duration = self.duration();
+        let last = self.frames.len().checked_sub(1)?;
+        match self.clamped_mode() {
+            Mode::Once => Some(duration),
+            Mode::RepeatTimes { from, times } => Some(
+                duration.saturating_add(
+                    self.loop_duration(from, last)
+                        .saturating_mul(times.get() - 1),
+                ),
+            ),
+            Mode::PingPongTimes(times) if last == 0 => Some(duration.saturating_mul(times.get())),
+            Mode::PingPongTimes(times) => {
+                let extra_sweeps = times.get() - 1;
+                let backward = self.frame_start(last);
+                Some(
+                    duration
+                        .saturating_add(self.ping_pong_cycle().saturating_mul(extra_sweeps / 2))
+                        .saturating_add(backward.saturating_mul(extra_sweeps % 2)),
+                )
+            }
+            Mode::RepeatFrom(_) | Mode::PingPong | Mode::Sustain { .. } => None,
+        }
+    }
+
+    /// Returns the time elapsed since the beginning of the animation, for the given play-head
+    ///
+    /// This is the inverse of [`frame_at`](Self::frame_at)
+    pub(crate) fn time_at(
+        &self,
+        position: usize,
+        elapsed_in_frame: Duration,
+        going_backward: bool,
+        loops: u32,
+    ) -> Duration {
+        let last = self.frames.len() - 1;
+        let position = position.min(last);
+        let in_pass = self.frame_start(position) + elapsed_in_frame;
+        match self.clamped_mode() {
+            Mode::Once => in_pass,
+            Mode::RepeatFrom(from) | Mode::RepeatTimes { from, .. } => {
+                in_pass.saturating_add(self.loop_duration(from, last).saturating_mul(loops))
+            }
+            Mode::Sustain {
+                loop_start,
+                loop_end,
+            } => in_pass.saturating_add(
+                self.loop_duration(loop_start, loop_end)
+                    .saturating_mul(loops),
+            ),
+            Mode::PingPong | Mode::PingPongTimes(_) if last == 0 => {
+                in_pass.saturating_add(self.duration().saturating_mul(loops))
+            }
+            Mode::PingPong | Mode::PingPongTimes(_) if loops == 0 => in_pass,
+            Mode::PingPong | Mode::PingPongTimes(_) => {
+                let backward = self.frame_start(last);
+                let cycles = self.ping_pong_cycle().saturating_mul((loops - 1) / 2);
+                let in_sweep = if going_backward {
+                    backward.saturating_sub(self.frame_ends[position]) + elapsed_in_frame
+                } else {
+                    backward + in_pass.saturating_sub(self.frames[0].duration)
+                };
+                self.duration()
+                    .saturating_add(cycles)
+                    .saturating_add(in_sweep)
+            }
+        }
+    }
+
+    /// Duration of the frames from `start` to `end` (inclusive)
+    pub(super) fn loop_duration(&self, start: usize, end: usize) -> Duration {
+        self.frame_ends[end].saturating_sub(self.frame_start(start))
+    }
+
+    /// Duration of a backward and a forward sweep of a ping-pong animation, without the first pass
+    pub(super) fn ping_pong_cycle(&self) -> Duration {
+        let last = self.frames.len() - 1;
+        self.frame_start(last) + self.duration().saturating_sub(self.frames[0].duration)
+    }
+}
+
+impl<T: SpriteIndex> Animation<T> {
+    /// Returns the index in the sprite atlas of the frame at `position`, if its value has one
+    pub(crate) fn sprite_index(&self, position: usize) -> Option<usize> {
+        self.frames[position].value.sprite_index()
+    }
+
+    /// Sample a repeating animation, which loops over the frames from `start` to `end` (inclusive)
+    fn sample_loop(&self, elapsed: Duration, start: usize, end: usize) -> Sample {
+        let loop_start = self.frame_start(start);
+        if elapsed < loop_start {
+            return self.sample_forward(elapsed);
+        }
+        let (loops, in_loop) = div_rem(
+            elapsed.saturating_sub(loop_start),
+            self.loop_duration(start, end),
+        );
+        Sample {
+            loops,
+            ..self.sample_forward(loop_start + in_loop)
+        }
+    }
+
+    fn sample_ping_pong(&self, elapsed: Duration) -> Sample {
+        let last = self.frames.len() - 1;
+        let duration = self.duration();
+        if last == 0 {
+            let (loops, in_frame) = div_rem(elapsed, duration);
+            return Sample {
+                loops,
+                ..self.sample_forward(in_frame)
+            };
+        }
+        if elapsed < duration {
+            return self.sample_forward(elapsed);
+        }
+        let backward = self.frame_start(last);
+        let (cycles, in_cycle) = div_rem(elapsed.saturating_sub(duration), self.ping_pong_cycle());
+        let loops = cycles.saturating_mul(2);
+        if in_cycle < backward {
+            Sample {
+                loops: loops.saturating_add(1),
+                ..self.sample_backward(backward.saturating_sub(in_cycle))
+            }
+        } else {
+            Sample {
+                loops: loops.saturating_add(2),
+                ..self.sample_forward(self.frames[0].duration + in_cycle.saturating_sub(backward))
+            }
+        }
+    }
+
+    /// Sample the last frame displayed by an animation that ended
+    fn sample_end(&self) -> Sample {
+        let last = self.frames.len() - 1;
+        let (position, going_backward, loops) = match self.mode {
+            Mode::PingPongTimes(times) if last > 0 && times.get() % 2 == 0 => {
+                (0, true, times.get() - 1)
+            }
+            Mode::PingPongTimes(times) | Mode::RepeatTimes { times, .. } => {
+                (last, false, times.get() - 1)
+            }
+            _ => (last, false, 0),
+        };
+        Sample {
+            index: self.sprite_index(position),
+            position,
+            elapsed_in_frame: self.frames[position].duration,
+            going_backward,
+            is_ended: true,
+            loops,
+        }
+    }
+
+    /// Sample the frame displayed at `time` (must be less than the animation duration) while going forward
+    fn sample_forward(&self, time: Duration) -> Sample {
+        let position = self.frame_ends.partition_point(|end| *end <= time);
+        Sample {
+            index: self.sprite_index(position),
+            position,
+            elapsed_in_frame: time.saturating_sub(self.frame_start(position)),
+            going_backward: false,
+            is_ended: false,
+            loops: 0,
+        }
+    }
+
+    /// Sample the frame displayed while going backward, `time` being the end of the remaining time in the frame
+    fn sample_backward(&self, time: Duration) -> Sample {
+        let position = self.frame_ends.partition_point(|end| *end < time);
+        Sample {
+            index: self.sprite_index(position),
+            position,
+            elapsed_in_frame: self.frame_ends[position].saturating_sub(time),
+            going_backward: true,
+            is_ended: false,
+            loops: 0,
+        }
+    }
+}
+
+/// Quotient (saturated) and remainder of the division of `duration` by `divisor`
+fn div_rem(duration: Duration, divisor: Duration) -> (u32, Duration) {
+    let (duration, divisor) = (duration.as_nanos(), divisor.as_nanos());
+    let nanos = duration % divisor;
+    (
+        u32::try_from(duration / divisor).unwrap_or(u32::MAX),
+        Duration::new(
+            u64::try_from(nanos / 1_000_000_000).unwrap_or(u64::MAX),
+            u32::try_from(nanos % 1_000_000_000).unwrap_or_default(),
+        ),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FrameRate, State};
+    use core::num::NonZeroU32;
+
+    #[fixture]
+    fn frame_rate() -> FrameRate {
+        FrameRate::from_frame_duration(Duration::from_secs(1))
+    }
+
+    #[rstest]
+    #[case(Duration::ZERO, 0, Duration::ZERO)]
+    #[case(Duration::from_millis(500), 0, Duration::from_millis(500))]
+    #[case(Duration::from_millis(1500), 1, Duration::from_millis(500))]
+    #[case(Duration::from_secs(3), 3, Duration::ZERO)]
+    #[case(Duration::from_millis(4500), 0, Duration::from_millis(500))]
+    fn repeat(
+        frame_rate: FrameRate,
+        #[case] elapsed: Duration,
+        #[case] position: usize,
+        #[case] elapsed_in_frame: Duration,
+    ) {
+        let sample = Animation::from_indices(10..=13, frame_rate).frame_at(elapsed);
+        assert_eq!(sample.position(), position);
+        assert_eq!(sample.index(), Some(position + 10));
+        assert_eq!(sample.elapsed_in_frame(), elapsed_in_frame);
+        assert!(!sample.is_ended());
+    }
+
+    #[rstest]
+    fn once_ends_on_last_frame(frame_rate: FrameRate) {
+        let sample = Animation::from_indices(0..=3, frame_rate)
+            .once()
+            .frame_at(Duration::from_secs(10));
+        assert_eq!(sample.position(), 3);
+        assert!(sample.is_ended());
+    }
+
+    #[rstest]
+    #[case(NonZeroU32::new(1).unwrap(), 3)]
+    #[case(NonZeroU32::new(2).unwrap(), 0)]
+    #[case(NonZeroU32::new(3).unwrap(), 3)]
+    fn ping_pong_times_ends_on_last_sweep(
+        frame_rate: FrameRate,
+        #[case] times: NonZeroU32,
+        #[case] position: usize,
+    ) {
+        let sample = Animation::from_indices(0..=3, frame_rate)
+            .ping_pong_times(times)
+            .frame_at(Duration::from_secs(100));
+        assert_eq!(sample.position(), position);
+        assert!(sample.is_ended());
+    }
+
+    #[rstest]
+    fn repeat_times_ends_after_last_pass(frame_rate: FrameRate) {
+        let animation =
+            Animation::from_indices(0..=3, frame_rate).repeat_times(NonZeroU32::new(2).unwrap());
+        assert!(!animation.frame_at(Duration::from_millis(7999)).is_ended());
+        assert!(animation.frame_at(Duration::from_secs(8)).is_ended());
+    }
+
+    #[rstest]
+    fn sustain_repeats_loop(frame_rate: FrameRate) {
+        let animation = Animation::from_indices(0..=4, frame_rate).sustain(1..=2);
+        let positions: Vec<usize> = (0..7)
+            .map(|i| animation.frame_at(Duration::from_secs(i)).position())
+            .collect();
+        assert_eq!(positions, vec![0, 1, 2, 1, 2, 1, 2]);
+    }
+
+    #[rstest]
+    fn handles_large_elapsed_time(frame_rate: FrameRate) {
+        let sample = Animation::from_indices(0..=3, frame_rate).frame_at(Duration::MAX);
+        assert!(sample.position() < 4);
+    }
+
+    #[rstest]
+    #[should_panic(expected = "no frame")]
+    fn panics_without_frames() {
+        let _ = Animation::<usize>::from_frames([]).frame_at(Duration::ZERO);
+    }
+
+    /// Compare the sample with a state updated frame by frame
+    #[rstest]
+    fn same_as_state(
+        #[values(
+            Animation::from_indices(0..=0, FrameRate::from_fps(1.0)),
+            Animation::from_indices(0..=3, FrameRate::from_fps(1.0)),
+            Animation::from_indices(0..=3, FrameRate::from_fps(1.0)).repeat_from(2),
+            Animation::from_indices(0..=3, FrameRate::from_fps(1.0)).repeat_from(3),
+            Animation::from_indices(0..=3, FrameRate::from_fps(1.0)).once(),
+            Animation::from_indices(0..=0, FrameRate::from_fps(1.0)).ping_pong(),
+            Animation::from_indices(0..=1, FrameRate::from_fps(1.0)).ping_pong(),
+            Animation::from_indices(0..=3, FrameRate::from_fps(1.0)).ping_pong(),
+            Animation::from_indices(0..=3, FrameRate::from_fps(1.0))
+                .repeat_from_times(2, NonZeroU32::new(3).unwrap()),
+            Animation::from_indices(0..=0, FrameRate::from_fps(1.0))
+                .ping_pong_times(NonZeroU32::new(3).unwrap()),
+            Animation::from_indices(0..=3, FrameRate::from_fps(1.0))
+                .ping_pong_times(NonZeroU32::new(4).unwrap()),
+            Animation::from_indices(0..=3, FrameRate::from_fps(1.0))
+                .ping_pong_times(NonZeroU32::new(5).unwrap()),
+            Animation::from_indices(0..=4, FrameRate::from_fps(1.0)).sustain(1..=2),
+            Animation::from_indices(0..=4, FrameRate::from_fps(1.0)).sustain(1..=9),
+            Animation::from_indices(0..=4, FrameRate::from_fps(1.0)).sustain(7..=9),
+            Animation::from_indices(0..=3, FrameRate::from_fps(1.0)).repeat_from(8),
+            Animation::from_frames([
+                crate::Frame::new(0, Duration::from_millis(300)),
+                crate::Frame::new(1, Duration::from_millis(700)),
+                crate::Frame::new(2, Duration::from_millis(200)),
+                crate::Frame::new(3, Duration::from_millis(900)),
+            ])
+            .ping_pong(),
+        )]
+        animation: Animation,
+    ) {
+        let step = Duration::from_millis(100);
+        let mut state = State::new();
+        for i in 0..200 {
+            let sample = animation.frame_at(step * i);
+            assert_eq!(sample.index(), Some(state.frame_index()), "at step {i}");
+            assert_eq!(
+                sample.elapsed_in_frame(),
+                state.elapsed_in_frame(),
+                "at step {i}"
+            );
+            assert_eq!(sample.is_ended(), state.is_ended(), "at step {i}");
+            if !sample.is_ended() {
+                assert_eq!(
+                    animation.time_at(
+                        sample.position,
+                        sample.elapsed_in_frame,
+                        sample.going_backward,
+                        sample.loops
+                    ),
+                    step * i,
+                    "at step {i}"
+                );
+            }
+            state.update(&animation, step);
+        }
+    }
+}
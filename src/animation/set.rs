@@ -0,0 +1,248 @@
+use std::{
+    collections::BTreeMap,
+    error::Error,
+    fmt::{self, Display, Formatter},
+};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::Animation;
+
+/// Collection of named animations
+///
+/// # Example
+///
+/// ```
+/// use benimator::*;
+///
+/// let set = AnimationSet::new()
+///     .with_animation("idle", Animation::from_indices(0..=3, FrameRate::from_fps(10.0)))
+///     .with_animation("walk", Animation::from_indices(4..=9, FrameRate::from_fps(10.0)));
+///
+/// assert!(set.get("walk").is_ok());
+/// assert_eq!(
+///     set.get("attack").unwrap_err().to_string(),
+///     "no animation named \"attack\" (available animations: idle, walk)",
+/// );
+/// ```
+#[cfg_attr(
+    feature = "serde",
+    doc = "
+
+# Deserialization format
+
+```yaml
+# Defaults for all the animations of the set.
+# They have the same meaning as in a single animation, and are all optional.
+mode: Repeat
+fps: 12 # or 'frame_duration' or 'total_duration'
+
+animations:
+  idle:
+    frames: [0, 1, 2, 3] # uses the defaults of the set
+  walk:
+    fps: 16 # overrides the frame-rate of the set
+    frames: [4, 5, 6, 7, 8, 9]
+  attack:
+    mode: Once # overrides the mode of the set
+    frames:
+      - index: 10
+        duration: 50
+      - index: 11
+        duration: 150
+```
+"
+)]
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(
+        try_from = "super::dto::AnimationSetDto<T>",
+        into = "super::dto::AnimationSetDto<T>",
+        bound(
+            serialize = "T: Serialize + Clone",
+            deserialize = "T: Deserialize<'de>"
+        )
+    )
+)]
+pub struct AnimationSet<T = usize> {
+    pub(crate) animations: BTreeMap<String, Animation<T>>,
+}
+
+/// Error returned when looking up a name that is not in an [`AnimationSet`]
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct MissingAnimation {
+    name: String,
+    available: Vec<String>,
+}
+
+impl AnimationSet {
+    /// Create an empty animation set
+    ///
+    /// For animations which frames are not indices in a sprite atlas, use [`AnimationSet::default`]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<T> AnimationSet<T> {
+    /// Add an animation to the set
+    ///
+    /// It replaces any animation previously added with the same name.
+    #[must_use]
+    pub fn with_animation(mut self, name: impl Into<String>, animation: Animation<T>) -> Self {
+        self.animations.insert(name.into(), animation);
+        self
+    }
+
+    /// Returns the animation with the given name
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there is no animation with that name in the set
+    pub fn get(&self, name: &str) -> Result<&Animation<T>, MissingAnimation> {
+        self.animations.get(name).ok_or_else(|| MissingAnimation {
+            name: name.into(),
+            available: self.animations.keys().cloned().collect(),
+        })
+    }
+
+    /// Iterate over the names and animations of the set, in alphabetical order of the names
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Animation<T>)> {
+        self.animations
+            .iter()
+            .map(|(name, animation)| (name.as_str(), animation))
+    }
+
+    /// Iterate over the names of the animations, in alphabetical order
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.animations.keys().map(String::as_str)
+    }
+
+    /// Returns the number of animations in the set
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.animations.len()
+    }
+
+    /// Returns true if the set contains no animation
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.animations.is_empty()
+    }
+}
+
+impl<T> Default for AnimationSet<T> {
+    fn default() -> Self {
+        Self {
+            animations: BTreeMap::new(),
+        }
+    }
+}
+
+impl<T, N: Into<String>> FromIterator<(N, Animation<T>)> for AnimationSet<T> {
+    fn from_iter<I: IntoIterator<Item = (N, Animation<T>)>>(iter: I) -> Self {
+        Self {
+            animations: iter
+                .into_iter()
+                .map(|(name, animation)| (name.into(), animation))
+                .collect(),
+        }
+    }
+}
+
+impl<T, N: Into<String>> Extend<(N, Animation<T>)> for AnimationSet<T> {
+    fn extend<I: IntoIterator<Item = (N, Animation<T>)>>(&mut self, iter: I) {
+        self.animations.extend(
+            iter.into_iter()
+                .map(|(name, animation)| (name.into(), animation)),
+        );
+    }
+}
+
+impl MissingAnimation {
+    /// Returns the name that was not found
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl Display for MissingAnimation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "no animation named {:?}", self.name)?;
+        if self.available.is_empty() {
+            write!(f, " (the set is empty)")
+        } else {
+            write!(f, " (available animations: {})", self.available.join(", "))
+        }
+    }
+}
+
+impl Error for MissingAnimation {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FrameRate;
+
+    #[fixture]
+    fn set() -> AnimationSet {
+        let frame_rate = FrameRate::from_fps(10.0);
+        AnimationSet::new()
+            .with_animation("walk", Animation::from_indices(4..=9, frame_rate))
+            .with_animation("idle", Animation::from_indices(0..=3, frame_rate))
+    }
+
+    #[rstest]
+    fn get(set: AnimationSet) {
+        assert_eq!(
+            set.get("idle"),
+            Ok(&Animation::from_indices(0..=3, FrameRate::from_fps(10.0)))
+        );
+    }
+
+    #[rstest]
+    fn missing_animation(set: AnimationSet) {
+        let error = set.get("attack").unwrap_err();
+        assert_eq!(error.name(), "attack");
+        assert_eq!(
+            error.to_string(),
+            "no animation named \"attack\" (available animations: idle, walk)"
+        );
+    }
+
+    #[test]
+    fn missing_animation_in_empty_set() {
+        assert_eq!(
+            AnimationSet::new().get("idle").unwrap_err().to_string(),
+            "no animation named \"idle\" (the set is empty)"
+        );
+    }
+
+    #[rstest]
+    fn names_are_sorted(set: AnimationSet) {
+        assert_eq!(set.names().collect::<Vec<_>>(), vec!["idle", "walk"]);
+        assert_eq!(set.len(), 2);
+    }
+
+    #[rstest]
+    fn replaces_animation_with_same_name(set: AnimationSet) {
+        let animation = Animation::from_indices(0..=0, FrameRate::from_fps(1.0));
+        let set = set.with_animation("idle", animation.clone());
+        assert_eq!(set.get("idle"), Ok(&animation));
+        assert_eq!(set.len(), 2);
+    }
+
+    #[rstest]
+    fn from_iterator(set: AnimationSet) {
+        let collected: AnimationSet = set
+            .iter()
+            .map(|(name, animation)| (name, animation.clone()))
+            .collect();
+        assert_eq!(collected, set);
+    }
+}
@@ -0,0 +1,488 @@
+use std::{
+    fmt::{self, Display, Formatter},
+    time::Duration,
+};
+
+use super::{Animation, SpriteIndex};
+
+/// Options of the validation of an [`Animation`]
+///
+/// # Example
+///
+/// ```
+/// use benimator::*;
+///
+/// let animation = Animation::from_indices(0..=11, FrameRate::from_fps(10.0)).repeat_from(20);
+/// let report = Validation::new().with_atlas_size(10).validate(&animation);
+///
+/// assert!(!report.is_valid());
+/// assert_eq!(report.problems().len(), 3); // 2 indices out of the atlas, and the mode refers to a missing frame
+/// ```
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[must_use]
+pub struct Validation {
+    atlas_size: Option<usize>,
+    max_frame_duration: Duration,
+}
+
+/// Result of the validation of an [`Animation`], listing all the problems found
+///
+/// See [`Animation::validate`] and [`Validation::validate`]
+#[derive(Debug, Clone, Eq, PartialEq, Default)]
+pub struct ValidationReport {
+    problems: Vec<Problem>,
+}
+
+/// Problem found by the validation of an [`Animation`]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum Problem {
+    /// The animation has no frame
+    NoFrame,
+    /// The mode refers to a frame position that does not exist
+    PositionOutOfRange {
+        /// Frame position referred to by the mode
+        position: usize,
+        /// Number of frames in the animation
+        frame_count: usize,
+    },
+    /// The index of a frame is out of the sprite atlas
+    IndexOutOfAtlas {
+        /// Position of the frame in the animation
+        position: usize,
+        /// Index of the frame in the sprite atlas
+        index: usize,
+        /// Number of sprites in the atlas
+        atlas_size: usize,
+    },
+    /// The offset, pivot or rotation of the [metadata](crate::FrameMetadata) of a frame is NaN or infinite
+    NonFiniteMetadata {
+        /// Position of the frame in the animation
+        position: usize,
+    },
+    /// A [marker](crate::Animation::with_marker) refers to a frame position that does not exist
+    MarkerOutOfRange {
+        /// Frame position referred to by the marker
+        position: usize,
+        /// Number of frames in the animation
+        frame_count: usize,
+    },
+    /// The offset of a [marker](crate::Animation::with_marker_at) is not before the end of its frame,
+    /// so that it is never reported
+    MarkerAfterFrameEnd {
+        /// Position of the frame in the animation
+        position: usize,
+        /// Offset of the marker since the beginning of the frame
+        offset: Duration,
+        /// Duration of the frame
+        duration: Duration,
+    },
+    /// The duration of a frame is suspiciously long
+    LongDuration {
+        /// Position of the frame in the animation
+        position: usize,
+        /// Duration of the frame
+        duration: Duration,
+    },
+}
+
+/// Severity of a [`Problem`]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[non_exhaustive]
+pub enum Severity {
+    /// The animation can be played, but may not be what was intended
+    Warning,
+    /// The animation cannot be played correctly
+    Error,
+}
+
+impl<T> Animation<T> {
+    /// Check the animation for problems, with the default [`Validation`] options
+    ///
+    /// An animation that has no frame, or with a mode or a marker that refers to a frame that does not exist,
+    /// cannot be played correctly.
+    #[must_use]
+    pub fn validate(&self) -> ValidationReport {
+        Validation::default().report(self, |_| None)
+    }
+}
+
+impl Default for Validation {
+    fn default() -> Self {
+        Self {
+            atlas_size: None,
+            max_frame_duration: Duration::from_secs(60),
+        }
+    }
+}
+
+impl Validation {
+    /// Create the default validation options
+    ///
+    /// The indices are not checked against any atlas size,
+    /// and the frames longer than a minute are reported as [`Problem::LongDuration`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Report the frames with an index that is not smaller than `atlas_size`
+    ///
+    /// Only the frames which value has a [sprite index](SpriteIndex::sprite_index) in a single atlas are checked,
+    /// since the atlases of the [`AtlasIndex`](crate::AtlasIndex) values may have different sizes.
+    pub fn with_atlas_size(mut self, atlas_size: usize) -> Self {
+        self.atlas_size = Some(atlas_size);
+        self
+    }
+
+    /// Report the frames with a duration longer than `duration`
+    pub fn with_max_frame_duration(mut self, duration: Duration) -> Self {
+        self.max_frame_duration = duration;
+        self
+    }
+
+    /// Check the animation for problems
+    #[must_use]
+    pub fn validate<T: SpriteIndex>(&self, animation: &Animation<T>) -> ValidationReport {
+        self.report(animation, |value| {
+            value.atlas().map_or_else(|| value.sprite_index(), |_| None)
+        })
+    }
+
+    /// Check the animation for problems, `index_in_atlas` returning the index checked against the atlas size
+    fn report<T>(
+        &self,
+        animation: &Animation<T>,
+        index_in_atlas: impl Fn(&T) -> Option<usize>,
+    ) -> ValidationReport {
+        let mut problems = Vec::new();
+        let frame_count = animation.frames.len();
+        if frame_count == 0 {
+            problems.push(Problem::NoFrame);
+        } else {
+            problems.extend(
+                animation
+                    .mode
+                    .positions()
+                    .filter(|&position| position >= frame_count)
+                    .map(|position| Problem::PositionOutOfRange {
+                        position,
+                        frame_count,
+                    }),
+            );
+        }
+        for (position, frame) in animation.frames.iter().enumerate() {
+            if let (Some(atlas_size), Some(index)) = (self.atlas_size, index_in_atlas(&frame.value))
+            {
+                if index >= atlas_size {
+                    problems.push(Problem::IndexOutOfAtlas {
+                        position,
+                        index,
+                        atlas_size,
+                    });
+                }
+            }
+            if !frame.metadata.is_finite() {
+                problems.push(Problem::NonFiniteMetadata { position });
+            }
+            if frame.duration > self.max_frame_duration {
+                problems.push(Problem::LongDuration {
+                    position,
+                    duration: frame.duration,
+                });
+            }
+        }
+        for marker in &animation.markers {
+            match animation.frames.get(marker.position) {
+                None => problems.push(Problem::MarkerOutOfRange {
+                    position: marker.position,
+                    frame_count,
+                }),
+                Some(frame) if marker.offset >= frame.duration => {
+                    problems.push(Problem::MarkerAfterFrameEnd {
+                        position: marker.position,
+                        offset: marker.offset,
+                        duration: frame.duration,
+                    });
+                }
+                Some(_) => (),
+            }
+        }
+        ValidationReport { problems }
+    }
+}
+
+impl ValidationReport {
+    /// Returns true if no problem of [`Severity::Error`] was found
+    #[must_use]
+    pub fn is_valid(&self) -> bool {
+        self.errors().next().is_none()
+    }
+
+    /// Returns true if no problem at all was found
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.problems.is_empty()
+    }
+
+    /// Returns all the problems found
+    #[must_use]
+    pub fn problems(&self) -> &[Problem] {
+        &self.problems
+    }
+
+    /// Iterate over the problems of [`Severity::Error`]
+    pub fn errors(&self) -> impl Iterator<Item = &Problem> {
+        self.with_severity(Severity::Error)
+    }
+
+    /// Iterate over the problems of [`Severity::Warning`]
+    pub fn warnings(&self) -> impl Iterator<Item = &Problem> {
+        self.with_severity(Severity::Warning)
+    }
+
+    fn with_severity(&self, severity: Severity) -> impl Iterator<Item = &Problem> {
+        self.problems
+            .iter()
+            .filter(move |problem| problem.severity() == severity)
+    }
+}
+
+impl Problem {
+    /// Returns how serious the problem is
+    #[must_use]
+    pub fn severity(&self) -> Severity {
+        match self {
+            Problem::NoFrame
+            | Problem::PositionOutOfRange { .. }
+            | Problem::IndexOutOfAtlas { .. }
+            | Problem::NonFiniteMetadata { .. }
+            | Problem::MarkerOutOfRange { .. }
+            | Problem::MarkerAfterFrameEnd { .. } => Severity::Error,
+            Problem::LongDuration { .. } => Severity::Warning,
+        }
+    }
+
+    /// Returns the position of the frame where the problem is, if it concerns a single frame
+    #[must_use]
+    pub fn position(&self) -> Option<usize> {
+        match self {
+            Problem::NoFrame
+            | Problem::PositionOutOfRange { .. }
+            | Problem::MarkerOutOfRange { .. } => None,
+            Problem::IndexOutOfAtlas { position, .. }
+            | Problem::NonFiniteMetadata { position }
+            | Problem::MarkerAfterFrameEnd { position, .. }
+            | Problem::LongDuration { position, .. } => Some(*position),
+        }
+    }
+}
+
+impl Display for ValidationReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (i, problem) in self.problems.iter().enumerate() {
+            if i > 0 {
+                write!(f, "; ")?;
+            }
+            write!(f, "{problem}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for Problem {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Problem::NoFrame => write!(f, "the animation has no frame"),
+            Problem::PositionOutOfRange {
+                position,
+                frame_count,
+            } => write!(
+                f,
+                "the mode refers to frame {position}, but there are only {frame_count} frames"
+            ),
+            Problem::IndexOutOfAtlas {
+                position,
+                index,
+                atlas_size,
+            } => write!(
+                f,
+                "frame {position}: index {index} is out of the atlas of {atlas_size} sprites"
+            ),
+            Problem::NonFiniteMetadata { position } => {
+                write!(
+                    f,
+                    "frame {position}: the offset, pivot or rotation is not finite"
+                )
+            }
+            Problem::MarkerOutOfRange {
+                position,
+                frame_count,
+            } => write!(
+                f,
+                "a marker refers to frame {position}, but there are only {frame_count} frames"
+            ),
+            Problem::MarkerAfterFrameEnd {
+                position,
+                offset,
+                duration,
+            } => write!(
+                f,
+                "frame {position}: marker offset of {offset:?} is not before the end of the frame ({duration:?})"
+            ),
+            Problem::LongDuration { position, duration } => {
+                write!(
+                    f,
+                    "frame {position}: duration of {duration:?} is suspiciously long"
+                )
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Frame, FrameMetadata, FrameRate, Mode};
+
+    #[test]
+    fn valid_animation() {
+        let animation = Animation::from_indices(0..=3, FrameRate::from_fps(10.0));
+        assert!(animation.validate().is_empty());
+        assert!(animation.validate().is_valid());
+    }
+
+    #[test]
+    fn no_frame() {
+        let report = Animation::<usize>::from_frames([]).validate();
+        assert_eq!(report.problems(), &[Problem::NoFrame]);
+        assert!(!report.is_valid());
+    }
+
+    #[rstest]
+    #[case(Mode::RepeatFrom(4), &[4])]
+    #[case(Mode::Sustain { loop_start: 2, loop_end: 5 }, &[5])]
+    #[case(Mode::Sustain { loop_start: 4, loop_end: 5 }, &[4, 5])]
+    fn position_out_of_range(#[case] mode: Mode, #[case] positions: &[usize]) {
+        let animation = Animation::from_indices(0..=3, FrameRate::from_fps(10.0)).with_mode(mode);
+        let expected: Vec<Problem> = positions
+            .iter()
+            .map(|&position| Problem::PositionOutOfRange {
+                position,
+                frame_count: 4,
+            })
+            .collect();
+        assert_eq!(animation.validate().problems(), expected);
+    }
+
+    #[test]
+    fn index_out_of_atlas() {
+        let animation = Animation::from_indices([3, 4, 2], FrameRate::from_fps(10.0));
+        let report = Validation::new().with_atlas_size(4).validate(&animation);
+        assert_eq!(
+            report.problems(),
+            &[Problem::IndexOutOfAtlas {
+                position: 1,
+                index: 4,
+                atlas_size: 4
+            }]
+        );
+        assert_eq!(report.problems()[0].position(), Some(1));
+    }
+
+    #[test]
+    fn atlas_size_ignores_other_values() {
+        let animation = Animation::from_values(["a", "b"], FrameRate::from_fps(10.0));
+        let report = Validation::new().with_atlas_size(1).validate(&animation);
+        assert!(report.is_empty());
+    }
+
+    #[rstest]
+    #[case(FrameMetadata::new().with_offset(f32::NAN, 0.0))]
+    #[case(FrameMetadata::new().with_pivot(0.0, f32::INFINITY))]
+    #[case(FrameMetadata::new().with_rotation(f32::NEG_INFINITY))]
+    fn non_finite_metadata(#[case] metadata: FrameMetadata) {
+        let animation = Animation::from_frames([
+            Frame::new(0, Duration::from_millis(100)),
+            Frame::new(1, Duration::from_millis(100)).with_metadata(metadata),
+        ]);
+        let report = animation.validate();
+        assert_eq!(
+            report.problems(),
+            &[Problem::NonFiniteMetadata { position: 1 }]
+        );
+        assert!(!report.is_valid());
+    }
+
+    #[test]
+    fn marker_out_of_range() {
+        let animation =
+            Animation::from_indices(0..=1, FrameRate::from_fps(10.0)).with_marker(2, "footstep");
+        let report = animation.validate();
+        assert_eq!(
+            report.problems(),
+            &[Problem::MarkerOutOfRange {
+                position: 2,
+                frame_count: 2
+            }]
+        );
+        assert!(!report.is_valid());
+        assert_eq!(report.problems()[0].position(), None);
+    }
+
+    #[rstest]
+    #[case(Duration::from_millis(100))]
+    #[case(Duration::from_millis(150))]
+    fn marker_after_frame_end(#[case] offset: Duration) {
+        let animation = Animation::from_indices(0..=1, FrameRate::from_fps(10.0))
+            .with_marker_at(1, Duration::from_millis(99), "a")
+            .with_marker_at(1, offset, "b");
+        let report = animation.validate();
+        assert_eq!(
+            report.problems(),
+            &[Problem::MarkerAfterFrameEnd {
+                position: 1,
+                offset,
+                duration: Duration::from_millis(100)
+            }]
+        );
+        assert!(!report.is_valid());
+        assert_eq!(report.problems()[0].position(), Some(1));
+    }
+
+    #[test]
+    fn long_duration_is_warning() {
+        let animation = Animation::from_frames([
+            Frame::new(0, Duration::from_millis(100)),
+            Frame::new(1, Duration::from_secs(2)),
+        ]);
+        let report = Validation::new()
+            .with_max_frame_duration(Duration::from_secs(1))
+            .validate(&animation);
+        assert!(report.is_valid());
+        assert_eq!(report.errors().count(), 0);
+        assert_eq!(
+            report.warnings().collect::<Vec<_>>(),
+            vec![&Problem::LongDuration {
+                position: 1,
+                duration: Duration::from_secs(2)
+            }]
+        );
+    }
+
+    #[test]
+    fn reports_every_problem() {
+        let animation = Animation::from_frames([
+            Frame::new(7, Duration::from_secs(120)),
+            Frame::new(8, Duration::from_millis(100)),
+        ])
+        .repeat_from(3);
+        let report = Validation::new().with_atlas_size(8).validate(&animation);
+        assert_eq!(report.problems().len(), 3);
+        assert_eq!(
+            report.to_string(),
+            "the mode refers to frame 3, but there are only 2 frames; \
+            frame 0: duration of 120s is suspiciously long; \
+            frame 1: index 8 is out of the atlas of 8 sprites"
+        );
+    }
+}
@@ -0,0 +1,279 @@
+use std::{
+    error::Error,
+    ffi::OsStr,
+    fmt::{self, Display, Formatter},
+    io,
+    str::Utf8Error,
+};
+
+use ::bevy::{
+    app::{App, Update},
+    asset::{
+        io::Reader, Asset, AssetApp, AssetEvent, AssetLoader, Assets, AsyncReadExt, Handle,
+        LoadContext, UntypedAssetId, VisitAssetDependencies,
+    },
+    ecs::prelude::*,
+    log::warn,
+    reflect::TypePath,
+    time::Time,
+    utils::BoxedFuture,
+};
+
+use ron::{extensions::Extensions, Options};
+
+use super::{play, SpriteAnimation, SpriteAnimationState, SpriteAnimationSystem, Targets, Writers};
+use crate::Animation;
+
+pub(super) fn build(app: &mut App) {
+    app.init_asset::<Animation>()
+        .init_asset_loader::<AnimationLoader>()
+        .add_systems(
+            Update,
+            (reload, animate).chain().in_set(SpriteAnimationSystem),
+        );
+}
+
+impl Asset for Animation {}
+
+impl VisitAssetDependencies for Animation {
+    fn visit_dependencies(&self, _visit: &mut impl FnMut(UntypedAssetId)) {}
+}
+
+impl TypePath for Animation {
+    fn type_path() -> &'static str {
+        "benimator::Animation"
+    }
+
+    fn short_type_path() -> &'static str {
+        "Animation"
+    }
+}
+
+/// Loads the animation files, using the same format as the deserialization of [`Animation`]
+#[derive(Default)]
+struct AnimationLoader;
+
+#[derive(Debug)]
+enum LoadError {
+    Io(io::Error),
+    Utf8(Utf8Error),
+    Yaml(serde_yaml::Error),
+    Toml(toml::de::Error),
+    Json(serde_json::Error),
+    Ron(ron::error::SpannedError),
+    UnsupportedExtension,
+}
+
+impl AssetLoader for AnimationLoader {
+    type Asset = Animation;
+    type Settings = ();
+    type Error = LoadError;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        _settings: &'a Self::Settings,
+        load_context: &'a mut LoadContext<'_>,
+    ) -> BoxedFuture<'a, Result<Self::Asset, Self::Error>> {
+        Box::pin(async move {
+            let mut bytes = Vec::new();
+            reader
+                .read_to_end(&mut bytes)
+                .await
+                .map_err(LoadError::Io)?;
+            let extension = load_context.path().extension().and_then(OsStr::to_str);
+            let animation = parse(extension.unwrap_or_default(), &bytes)?;
+            // The deserialization only fails on errors, so that the warnings are reported here
+            for warning in animation.validate().warnings() {
+                warn!("{}: {warning}", load_context.path().display());
+            }
+            Ok(animation)
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &[
+            "animation.yml",
+            "animation.yaml",
+            "animation.toml",
+            "animation.json",
+            "animation.ron",
+        ]
+    }
+}
+
+fn parse(extension: &str, bytes: &[u8]) -> Result<Animation, LoadError> {
+    match extension {
+        "yml" | "yaml" => serde_yaml::from_slice(bytes).map_err(LoadError::Yaml),
+        "toml" => toml::from_str(std::str::from_utf8(bytes).map_err(LoadError::Utf8)?)
+            .map_err(LoadError::Toml),
+        "json" => serde_json::from_slice(bytes).map_err(LoadError::Json),
+        "ron" => Options::default()
+            .with_default_extension(Extensions::IMPLICIT_SOME)
+            .from_bytes(bytes)
+            .map_err(LoadError::Ron),
+        _ => Err(LoadError::UnsupportedExtension),
+    }
+}
+
+impl Display for LoadError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadError::Io(error) => write!(f, "cannot read the animation file: {error}"),
+            LoadError::Utf8(error) => write!(f, "invalid animation file: {error}"),
+            LoadError::Yaml(error) => write!(f, "invalid animation file: {error}"),
+            LoadError::Toml(error) => write!(f, "invalid animation file: {error}"),
+            LoadError::Json(error) => write!(f, "invalid animation file: {error}"),
+            LoadError::Ron(error) => write!(f, "invalid animation file: {error}"),
+            LoadError::UnsupportedExtension => write!(f, "unsupported animation file extension"),
+        }
+    }
+}
+
+impl Error for LoadError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            LoadError::Io(error) => Some(error),
+            LoadError::Utf8(error) => Some(error),
+            LoadError::Yaml(error) => Some(error),
+            LoadError::Toml(error) => Some(error),
+            LoadError::Json(error) => Some(error),
+            LoadError::Ron(error) => Some(error),
+            LoadError::UnsupportedExtension => None,
+        }
+    }
+}
+
+/// Keep the states of the entities at the same time position when their animation is modified (hot-reloaded)
+#[allow(clippy::needless_pass_by_value)]
+fn reload(
+    mut events: EventReader<'_, '_, AssetEvent<Animation>>,
+    animations: Res<'_, Assets<Animation>>,
+    mut query: Query<'_, '_, (&Handle<Animation>, &mut SpriteAnimationState)>,
+) {
+    for event in events.read() {
+        let AssetEvent::Modified { id } = event else {
+            continue;
+        };
+        let Some(animation) = animations.get(*id).filter(|a| a.has_frames()) else {
+            continue;
+        };
+        for (_, mut state) in query.iter_mut().filter(|(handle, _)| handle.id() == *id) {
+            let elapsed = state.elapsed();
+            state.seek(animation, elapsed);
+        }
+    }
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn animate(
+    time: Res<'_, Time>,
+    animations: Res<'_, Assets<Animation>>,
+    mut query: Query<
+        '_,
+        '_,
+        (
+            Entity,
+            &Handle<Animation>,
+            &mut SpriteAnimationState,
+            Targets<'_>,
+        ),
+        Without<SpriteAnimation>,
+    >,
+    mut writers: Writers<'_>,
+) {
+    for (entity, handle, mut state, targets) in &mut query {
+        if let Some(animation) = animations.get(handle) {
+            if animation.has_frames() && !state.is_bound_to(animation) {
+                // The animation was hot-reloaded, and the `reload` system did not receive the event yet
+                let elapsed = state.elapsed();
+                state.seek(animation, elapsed);
+            }
+            play(
+                entity,
+                animation,
+                &mut state,
+                targets,
+                time.delta(),
+                &mut writers,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use ::bevy::{asset::AssetPlugin, sprite::TextureAtlasSprite};
+
+    use super::*;
+    use crate::{bevy::BenimatorPlugin, FrameRate};
+
+    #[rstest]
+    #[case::yaml("yml", "fps: 10\nframes: [0, 1, 2]")]
+    #[case::toml("toml", "fps = 10\nframes = [0, 1, 2]")]
+    #[case::json("json", r#"{ "fps": 10, "frames": [0, 1, 2] }"#)]
+    #[case::ron("ron", "(mode: Repeat, fps: 10, frames: [0, 1, 2])")]
+    fn parses(#[case] extension: &str, #[case] content: &str) {
+        assert_eq!(
+            parse(extension, content.as_bytes()).unwrap(),
+            Animation::from_indices(0..=2, FrameRate::from_fps(10.0))
+        );
+    }
+
+    #[rstest]
+    #[case::invalid_animation("yml", "frames: [{ index: 0, duration: 0 }]")]
+    #[case::invalid_syntax("json", "{")]
+    #[case::unsupported_extension("txt", "fps: 10\nframes: [0, 1, 2]")]
+    fn invalid(#[case] extension: &str, #[case] content: &str) {
+        assert!(parse(extension, content.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn hot_reload_keeps_the_time_position() {
+        let mut app = App::new();
+        app.init_resource::<Time>()
+            .add_plugins((AssetPlugin::default(), BenimatorPlugin));
+        let handle = app
+            .world
+            .resource_mut::<Assets<Animation>>()
+            .add(Animation::from_indices(
+                0..=3,
+                FrameRate::from_frame_duration(Duration::from_secs(1)),
+            ));
+        let entity = app
+            .world
+            .spawn((
+                handle.clone(),
+                SpriteAnimationState::default(),
+                TextureAtlasSprite::default(),
+            ))
+            .id();
+        app.world
+            .resource_mut::<Time>()
+            .advance_by(Duration::from_millis(2500));
+        app.update();
+        assert_eq!(
+            app.world.get::<TextureAtlasSprite>(entity).unwrap().index,
+            2
+        );
+
+        app.world
+            .resource_mut::<Assets<Animation>>()
+            .get_mut(&handle)
+            .unwrap()
+            .set_frame_duration(0, Duration::from_millis(500));
+        app.world.resource_mut::<Time>().advance_by(Duration::ZERO);
+        app.update();
+        app.update();
+
+        let state = app.world.get::<SpriteAnimationState>(entity).unwrap();
+        assert_eq!(state.frame_index(), 3);
+        assert_eq!(state.elapsed_in_frame(), Duration::ZERO);
+        assert_eq!(
+            app.world.get::<TextureAtlasSprite>(entity).unwrap().index,
+            3
+        );
+    }
+}
@@ -0,0 +1,472 @@
+//! Integration with the [bevy](https://bevyengine.org) game engine
+//!
+//! Add the [`BenimatorPlugin`] to the app, and insert a [`SpriteAnimation`] and a [`SpriteAnimationState`]
+//! on entities that have a `TextureAtlasSprite` (sprite-sheet) or a `UiTextureAtlasImage` (UI).
+//! The plugin then updates the animation states and the atlas indices.
+//!
+//! The [metadata](crate::FrameMetadata) of the frames is applied as well:
+//! the flips to the `flip_x` and `flip_y` of the atlas components, the pivot to the anchor of the sprite,
+//! and the offset and rotation to the `Transform` of the entity (if it has one).
+//! They are applied relative to the previous frame, so that the entity may still be moved, rotated and flipped
+//! by the game.
+//!
+//! With the `bevy-asset` feature, [`Animation`] is also an asset, loaded from the files with the extensions
+//! `.animation.yml`, `.animation.yaml`, `.animation.toml`, `.animation.json` or `.animation.ron`,
+//! using the same format as its deserialization.
+//! A `Handle<Animation>` may then be inserted instead of the [`SpriteAnimation`].
+//! When the file is modified, and bevy's `file_watcher` feature is enabled, the running entities
+//! are updated in place, keeping the time elapsed since the start of their animation.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use bevy::prelude::*;
+//! use benimator::{bevy::*, FrameRate};
+//!
+//! fn spawn(mut commands: Commands) {
+//!     commands.spawn((
+//!         SpriteSheetBundle::default(),
+//!         SpriteAnimation::new(benimator::Animation::from_indices(0..=4, FrameRate::from_fps(12.0))),
+//!         SpriteAnimationState::default(),
+//!     ));
+//! }
+//!
+//! App::new()
+//!     .add_plugins((DefaultPlugins, BenimatorPlugin))
+//!     .add_systems(Startup, spawn)
+//!     .run();
+//! ```
+
+use core::ops::{Deref, DerefMut};
+use std::time::Duration;
+
+use ::bevy::{
+    app::{App, Plugin, Update},
+    ecs::{prelude::*, query::QueryItem, system::SystemParam},
+    math::Vec2,
+    sprite::{Anchor, TextureAtlasSprite},
+    time::Time,
+    transform::components::Transform,
+    ui::UiTextureAtlasImage,
+};
+
+use crate::{Animation, FrameMetadata, RebindPolicy, State};
+
+#[cfg(feature = "bevy-asset")]
+mod asset;
+
+/// Plugin updating the [`SpriteAnimationState`] of the entities, and their texture atlas index
+///
+/// The animations are updated in the `Update` schedule, in the [`SpriteAnimationSystem`] set,
+/// using the [`Time`] resource. So they are paused and slowed-down along with the virtual time.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct BenimatorPlugin;
+
+/// System set in which the sprite animations are updated
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, SystemSet)]
+pub struct SpriteAnimationSystem;
+
+/// Component holding the [`Animation`] played by an entity
+///
+/// When the animation is replaced, the entity keeps playing from the same frame position,
+/// wrapped around the number of frames of the new animation (see [`RebindPolicy::Wrap`]).
+/// The [`SpriteAnimationState`] may be reset to play the new animation from its beginning instead.
+#[derive(Debug, Clone, Component)]
+pub struct SpriteAnimation(Animation);
+
+/// Component holding the animation [`State`] of an entity
+#[derive(Debug, Clone, Default, Component)]
+pub struct SpriteAnimationState {
+    state: State,
+    /// Metadata of the frame which has been applied to the components of the entity
+    applied: FrameMetadata,
+}
+
+/// Event sent when the frame displayed by an entity has changed
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Event)]
+pub struct FrameChanged {
+    entity: Entity,
+    index: usize,
+}
+
+/// Event sent when the animation of an entity has ended
+///
+/// See [`crate::Event::Ended`]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Event)]
+pub struct AnimationEnded {
+    entity: Entity,
+}
+
+impl Plugin for BenimatorPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<FrameChanged>()
+            .add_event::<AnimationEnded>()
+            .add_systems(Update, animate.in_set(SpriteAnimationSystem));
+        #[cfg(feature = "bevy-asset")]
+        asset::build(app);
+    }
+}
+
+impl SpriteAnimation {
+    /// Create the component from an animation
+    #[must_use]
+    pub fn new(animation: Animation) -> Self {
+        Self(animation)
+    }
+}
+
+impl From<Animation> for SpriteAnimation {
+    fn from(animation: Animation) -> Self {
+        Self(animation)
+    }
+}
+
+impl Deref for SpriteAnimation {
+    type Target = Animation;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for SpriteAnimation {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl SpriteAnimationState {
+    /// Create the component from an animation state
+    #[must_use]
+    pub fn new(state: State) -> Self {
+        Self {
+            state,
+            applied: FrameMetadata::default(),
+        }
+    }
+}
+
+impl From<State> for SpriteAnimationState {
+    fn from(state: State) -> Self {
+        Self::new(state)
+    }
+}
+
+impl Deref for SpriteAnimationState {
+    type Target = State;
+
+    fn deref(&self) -> &Self::Target {
+        &self.state
+    }
+}
+
+impl DerefMut for SpriteAnimationState {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.state
+    }
+}
+
+impl FrameChanged {
+    /// Returns the entity which frame has changed
+    #[must_use]
+    pub fn entity(&self) -> Entity {
+        self.entity
+    }
+
+    /// Returns the index in the sprite atlas of the new frame
+    #[must_use]
+    pub fn index(&self) -> usize {
+        self.index
+    }
+}
+
+impl AnimationEnded {
+    /// Returns the entity which animation has ended
+    #[must_use]
+    pub fn entity(&self) -> Entity {
+        self.entity
+    }
+}
+
+/// Optional components updated by the animation: the texture atlases of which the index is updated,
+/// and the transform to which the offset and rotation of the frames are applied
+type Targets<'a> = (
+    Option<&'a mut TextureAtlasSprite>,
+    Option<&'a mut UiTextureAtlasImage>,
+    Option<&'a mut Transform>,
+);
+
+#[derive(SystemParam)]
+struct Writers<'w> {
+    frame_changed: EventWriter<'w, FrameChanged>,
+    ended: EventWriter<'w, AnimationEnded>,
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn animate(
+    time: Res<'_, Time>,
+    mut query: Query<
+        '_,
+        '_,
+        (
+            Entity,
+            &SpriteAnimation,
+            &mut SpriteAnimationState,
+            Targets<'_>,
+        ),
+    >,
+    mut writers: Writers<'_>,
+) {
+    for (entity, animation, mut state, targets) in &mut query {
+        if animation.has_frames() && !state.is_bound_to(animation) {
+            // The animation was replaced without resetting the state
+            state.rebind(animation, RebindPolicy::Wrap);
+        }
+        play(
+            entity,
+            animation,
+            &mut state,
+            targets,
+            time.delta(),
+            &mut writers,
+        );
+    }
+}
+
+fn play(
+    entity: Entity,
+    animation: &Animation,
+    state: &mut SpriteAnimationState,
+    (sprite, image, transform): QueryItem<'_, Targets<'static>>,
+    delta: Duration,
+    writers: &mut Writers<'_>,
+) {
+    if !animation.has_frames() {
+        return;
+    }
+    let outcome = state.state.update(animation, delta);
+    if outcome.just_ended() {
+        writers.ended.send(AnimationEnded { entity });
+    }
+    let index = state.frame_index();
+    if outcome.index_changed() {
+        writers.frame_changed.send(FrameChanged { entity, index });
+    }
+    let metadata = *state.frame_metadata(animation);
+    let applied = core::mem::replace(&mut state.applied, metadata);
+    if let Some(mut sprite) = sprite {
+        if sprite.index != index {
+            sprite.index = index;
+        }
+        if metadata.flip_x() != applied.flip_x() {
+            sprite.flip_x = !sprite.flip_x;
+        }
+        if metadata.flip_y() != applied.flip_y() {
+            sprite.flip_y = !sprite.flip_y;
+        }
+        if metadata.pivot() != applied.pivot() {
+            sprite.anchor = metadata
+                .pivot()
+                .map_or(Anchor::Center, |(x, y)| Anchor::Custom(Vec2::new(x, y)));
+        }
+    }
+    if let Some(mut image) = image {
+        if image.index != index {
+            image.index = index;
+        }
+        if metadata.flip_x() != applied.flip_x() {
+            image.flip_x = !image.flip_x;
+        }
+        if metadata.flip_y() != applied.flip_y() {
+            image.flip_y = !image.flip_y;
+        }
+    }
+    if let Some(mut transform) = transform {
+        let offset = Vec2::from(metadata.offset()) - Vec2::from(applied.offset());
+        if offset != Vec2::ZERO {
+            transform.translation += offset.extend(0.0);
+        }
+        let rotation = metadata.rotation() - applied.rotation();
+        if rotation != 0.0 {
+            transform.rotate_z(rotation);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_2;
+
+    use ::bevy::{
+        ecs::event::Events,
+        math::{Quat, Vec3},
+    };
+
+    use super::*;
+    use crate::{Frame, FrameRate};
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.init_resource::<Time>();
+        #[cfg(feature = "bevy-asset")]
+        app.add_plugins(::bevy::asset::AssetPlugin::default());
+        app.add_plugins(BenimatorPlugin);
+        app
+    }
+
+    fn animation() -> SpriteAnimation {
+        Animation::from_indices(
+            1..=3,
+            FrameRate::from_frame_duration(Duration::from_secs(1)),
+        )
+        .once()
+        .into()
+    }
+
+    fn advance(app: &mut App, delta: Duration) {
+        app.world.resource_mut::<Time>().advance_by(delta);
+        app.update();
+    }
+
+    #[test]
+    fn updates_sprite_index() {
+        let mut app = app();
+        let entity = app
+            .world
+            .spawn((
+                animation(),
+                SpriteAnimationState::default(),
+                TextureAtlasSprite::default(),
+            ))
+            .id();
+        advance(&mut app, Duration::from_millis(1500));
+        assert_eq!(
+            app.world.get::<TextureAtlasSprite>(entity).unwrap().index,
+            2
+        );
+    }
+
+    #[test]
+    fn replacing_the_animation_keeps_the_position() {
+        let mut app = app();
+        let entity = app
+            .world
+            .spawn((
+                animation(),
+                SpriteAnimationState::default(),
+                TextureAtlasSprite::default(),
+            ))
+            .id();
+        advance(&mut app, Duration::from_millis(2500));
+        app.world
+            .entity_mut(entity)
+            .insert(SpriteAnimation::new(Animation::from_indices(
+                4..=5,
+                FrameRate::from_frame_duration(Duration::from_secs(1)),
+            )));
+        advance(&mut app, Duration::ZERO);
+        assert_eq!(
+            app.world.get::<TextureAtlasSprite>(entity).unwrap().index,
+            4
+        );
+    }
+
+    #[test]
+    fn updates_ui_image_index() {
+        let mut app = app();
+        let entity = app
+            .world
+            .spawn((
+                animation(),
+                SpriteAnimationState::default(),
+                UiTextureAtlasImage::default(),
+            ))
+            .id();
+        advance(&mut app, Duration::from_millis(1500));
+        assert_eq!(
+            app.world.get::<UiTextureAtlasImage>(entity).unwrap().index,
+            2
+        );
+    }
+
+    #[test]
+    fn does_not_advance_when_time_is_paused() {
+        let mut app = app();
+        let entity = app
+            .world
+            .spawn((animation(), SpriteAnimationState::default()))
+            .id();
+        advance(&mut app, Duration::ZERO);
+        advance(&mut app, Duration::ZERO);
+        let state = app.world.get::<SpriteAnimationState>(entity).unwrap();
+        assert_eq!(state.frame_index(), 1);
+        assert_eq!(state.elapsed(), Duration::ZERO);
+    }
+
+    #[test]
+    fn applies_frame_metadata() {
+        let mut app = app();
+        let animation = Animation::from_frames([
+            Frame::new(0, Duration::from_secs(1)),
+            Frame::new(1, Duration::from_secs(1)).with_metadata(
+                FrameMetadata::new()
+                    .with_offset(2.0, -1.0)
+                    .with_pivot(0.0, -0.5)
+                    .with_flip_x(true)
+                    .with_rotation(FRAC_PI_2),
+            ),
+        ]);
+        let entity = app
+            .world
+            .spawn((
+                SpriteAnimation::new(animation),
+                SpriteAnimationState::default(),
+                TextureAtlasSprite::default(),
+                Transform::from_xyz(10.0, 0.0, 0.0),
+            ))
+            .id();
+
+        advance(&mut app, Duration::from_millis(1500));
+        let sprite = app.world.get::<TextureAtlasSprite>(entity).unwrap();
+        assert!(sprite.flip_x);
+        assert!(!sprite.flip_y);
+        assert_eq!(sprite.anchor.as_vec(), Vec2::new(0.0, -0.5));
+        let transform = app.world.get::<Transform>(entity).unwrap();
+        assert_eq!(transform.translation, Vec3::new(12.0, -1.0, 0.0));
+        assert!(transform
+            .rotation
+            .abs_diff_eq(Quat::from_rotation_z(FRAC_PI_2), 1e-6));
+
+        advance(&mut app, Duration::from_secs(1));
+        let sprite = app.world.get::<TextureAtlasSprite>(entity).unwrap();
+        assert!(!sprite.flip_x);
+        assert!(matches!(sprite.anchor, Anchor::Center));
+        let transform = app.world.get::<Transform>(entity).unwrap();
+        assert_eq!(transform.translation, Vec3::new(10.0, 0.0, 0.0));
+        assert!(transform.rotation.abs_diff_eq(Quat::IDENTITY, 1e-6));
+    }
+
+    #[test]
+    fn sends_events() {
+        let mut app = app();
+        let entity = app
+            .world
+            .spawn((animation(), SpriteAnimationState::default()))
+            .id();
+        advance(&mut app, Duration::from_millis(1500));
+        let changes: Vec<FrameChanged> = app
+            .world
+            .resource_mut::<Events<FrameChanged>>()
+            .drain()
+            .collect();
+        assert_eq!(changes, vec![FrameChanged { entity, index: 2 }]);
+
+        advance(&mut app, Duration::from_secs(10));
+        let ended: Vec<AnimationEnded> = app
+            .world
+            .resource_mut::<Events<AnimationEnded>>()
+            .drain()
+            .collect();
+        assert_eq!(ended, vec![AnimationEnded { entity }]);
+    }
+}
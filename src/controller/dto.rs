@@ -0,0 +1,242 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use super::{Condition, ConditionKind, Controller, Transition};
+use crate::{Animation, ControllerError};
+
+#[derive(Serialize, Deserialize)]
+#[serde(
+    deny_unknown_fields,
+    bound(
+        serialize = "T: Serialize + Clone",
+        deserialize = "T: Deserialize<'de>"
+    )
+)]
+pub(super) struct ControllerDto<T> {
+    states: Vec<StateDto<T>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    transitions: Vec<TransitionDto>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(
+    deny_unknown_fields,
+    bound(
+        serialize = "T: Serialize + Clone",
+        deserialize = "T: Deserialize<'de>"
+    )
+)]
+struct StateDto<T> {
+    name: String,
+    animation: Animation<T>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TransitionDto {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    from: Option<String>,
+    to: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    when: Vec<ConditionDto>,
+    #[serde(default, skip_serializing_if = "is_false")]
+    on_end: bool,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    exit_time: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+enum ConditionDto {
+    IsTrue(String),
+    IsFalse(String),
+    GreaterThan { param: String, value: f64 },
+    LessThan { param: String, value: f64 },
+    Triggered(String),
+}
+
+#[allow(clippy::trivially_copy_pass_by_ref)]
+fn is_false(value: &bool) -> bool {
+    !value
+}
+
+#[allow(clippy::trivially_copy_pass_by_ref)]
+fn is_zero(value: &u64) -> bool {
+    *value == 0
+}
+
+impl<T> From<Controller<T>> for ControllerDto<T> {
+    fn from(controller: Controller<T>) -> Self {
+        Self {
+            states: controller
+                .states
+                .into_iter()
+                .map(|(name, animation)| StateDto { name, animation })
+                .collect(),
+            transitions: controller
+                .transitions
+                .into_iter()
+                .map(|(_, _, transition)| TransitionDto {
+                    from: transition.from,
+                    to: transition.to,
+                    when: transition
+                        .conditions
+                        .into_iter()
+                        .map(|Condition(kind)| match kind {
+                            ConditionKind::IsTrue(param) => ConditionDto::IsTrue(param),
+                            ConditionKind::IsFalse(param) => ConditionDto::IsFalse(param),
+                            ConditionKind::GreaterThan(param, value) => {
+                                ConditionDto::GreaterThan { param, value }
+                            }
+                            ConditionKind::LessThan(param, value) => {
+                                ConditionDto::LessThan { param, value }
+                            }
+                            ConditionKind::Triggered(param) => ConditionDto::Triggered(param),
+                        })
+                        .collect(),
+                    on_end: transition.on_end,
+                    exit_time: u64::try_from(transition.exit_time.as_millis()).unwrap_or(u64::MAX),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl<T> TryFrom<ControllerDto<T>> for Controller<T> {
+    type Error = ControllerError;
+
+    fn try_from(controller: ControllerDto<T>) -> Result<Self, Self::Error> {
+        let mut result = Controller::default();
+        for StateDto { name, animation } in controller.states {
+            result = result.try_with_state(name, animation)?;
+        }
+        for transition in controller.transitions {
+            let mut result_transition = Transition {
+                from: transition.from,
+                ..Transition::from_any_state(transition.to)
+            }
+            .with_exit_time(Duration::from_millis(transition.exit_time));
+            if transition.on_end {
+                result_transition = result_transition.on_end();
+            }
+            for condition in transition.when {
+                result_transition = result_transition.when(match condition {
+                    ConditionDto::IsTrue(param) => Condition::is_true(param),
+                    ConditionDto::IsFalse(param) => Condition::is_false(param),
+                    ConditionDto::GreaterThan { param, value } => {
+                        Condition::greater_than(param, value)
+                    }
+                    ConditionDto::LessThan { param, value } => Condition::less_than(param, value),
+                    ConditionDto::Triggered(param) => Condition::triggered(param),
+                });
+            }
+            result = result.try_with_transition(result_transition)?;
+        }
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{AtlasIndex, ControllerState, FrameRate};
+
+    #[test]
+    fn deserialize_serialize() {
+        let frame_rate = FrameRate::from_fps(2.0);
+        let controller = Controller::new()
+            .with_state("idle", Animation::from_indices(0..=2, frame_rate))
+            .with_state("jump", Animation::from_indices(3..=4, frame_rate).once())
+            .with_transition(
+                Transition::new("idle", "jump")
+                    .when(Condition::triggered("jump"))
+                    .when(Condition::greater_than("speed", 0.5))
+                    .with_exit_time(Duration::from_millis(200)),
+            )
+            .with_transition(Transition::new("jump", "idle").on_end())
+            .with_transition(Transition::from_any_state("idle").when(Condition::is_true("reset")));
+
+        let yaml: String = serde_yaml::to_string(&controller).unwrap();
+        let from_yaml: Controller = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(controller, from_yaml);
+        let toml: String = toml::to_string(&controller).unwrap();
+        let from_toml: Controller = toml::from_str(&toml).unwrap();
+        assert_eq!(controller, from_toml);
+    }
+
+    #[test]
+    fn deserialize_yaml() {
+        // given
+        let content = "
+            states:
+              - name: idle
+                animation:
+                  fps: 10
+                  frames: [0, 1, 2, 3]
+              - name: jump
+                animation:
+                  mode: Once
+                  fps: 10
+                  frames: [8, 9]
+            transitions:
+              - from: idle
+                to: jump
+                when: [!Triggered jump]
+              - from: jump
+                to: idle
+                on_end: true";
+
+        // when
+        let controller: Controller = serde_yaml::from_str(content).unwrap();
+
+        // then
+        let mut state = ControllerState::new();
+        state.set_trigger("jump");
+        state.update(&controller, Duration::from_millis(50));
+        assert_eq!(state.current_state(&controller), "jump");
+        state.update(&controller, Duration::from_millis(200));
+        assert_eq!(state.current_state(&controller), "idle");
+    }
+
+    #[test]
+    fn deserialize_atlas_indices() {
+        let content = "
+            states:
+              - name: idle
+                animation:
+                  fps: 10
+                  frames: [{ atlas: hero, index: 0 }, { atlas: hero, index: 1 }]";
+        let controller: Controller<AtlasIndex> = serde_yaml::from_str(content).unwrap();
+        let mut state = ControllerState::new();
+        state.update(&controller, Duration::from_millis(150));
+        assert_eq!(state.frame_value(&controller), &AtlasIndex::new("hero", 1));
+    }
+
+    #[rstest]
+    #[case(
+        "
+        states:
+          - name: idle
+            animation: { fps: 10, frames: [0] }
+        transitions:
+          - to: run"
+    )]
+    #[case(
+        "
+        states:
+          - name: idle
+            animation: { fps: 10, frames: [0] }
+          - name: idle
+            animation: { fps: 10, frames: [1] }"
+    )]
+    #[case(
+        "
+        states:
+          - name: idle
+            animation: { fps: 10, frames: [] }"
+    )]
+    fn invalid(#[case] content: &str) {
+        assert!(serde_yaml::from_str::<Controller>(content).is_err());
+    }
+}
@@ -0,0 +1,820 @@
+use std::{
+    collections::{HashMap, HashSet},
+    time::Duration,
+};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{Animation, ControllerError, Event, SpriteIndex, State};
+
+#[cfg(feature = "serde")]
+mod dto;
+
+/// Definition of an animation state machine
+///
+/// A controller is made of named states, each bound to an [`Animation`],
+/// and of [`Transition`]s between those states.
+/// The first state added is the initial state.
+///
+/// Like the animations it plays, a controller is generic over the value of the frames,
+/// which is the index in the sprite atlas by default.
+///
+/// Like an [`Animation`], a controller is only a definition, and may be shared.
+/// The state machine is played with a [`ControllerState`].
+///
+/// # Example
+///
+/// ```
+/// # use std::time::Duration;
+/// use benimator::*;
+///
+/// let controller = Controller::new()
+///     .with_state("idle", Animation::from_indices(0..=3, FrameRate::from_fps(10.0)))
+///     .with_state("run", Animation::from_indices(4..=7, FrameRate::from_fps(10.0)))
+///     .with_state("jump", Animation::from_indices(8..=9, FrameRate::from_fps(10.0)).once())
+///     .with_transition(Transition::new("idle", "run").when(Condition::greater_than("speed", 0.1)))
+///     .with_transition(Transition::new("run", "idle").when(Condition::less_than("speed", 0.1)))
+///     .with_transition(Transition::from_any_state("jump").when(Condition::triggered("jump")))
+///     .with_transition(Transition::new("jump", "idle").on_end());
+///
+/// let mut state = ControllerState::new();
+/// state.update(&controller, Duration::from_millis(100));
+/// assert_eq!(state.current_state(&controller), "idle");
+///
+/// state.set_float("speed", 1.0);
+/// state.update(&controller, Duration::from_millis(100));
+/// assert_eq!(state.current_state(&controller), "run");
+///
+/// state.set_trigger("jump");
+/// state.update(&controller, Duration::from_millis(100));
+/// assert_eq!(state.current_state(&controller), "jump");
+/// ```
+#[cfg_attr(
+    feature = "serde",
+    doc = "
+
+# Deserialization format
+
+```yaml
+states:
+  - name: idle
+    animation: # same format as an animation
+      fps: 10
+      frames: [0, 1, 2, 3]
+  - name: jump
+    animation:
+      mode: Once
+      fps: 10
+      frames: [8, 9]
+transitions:
+  - from: idle
+    to: jump
+    when: [!Triggered jump] # also: !IsTrue p, !IsFalse p, !GreaterThan { param: p, value: x }, !LessThan {...}
+    exit_time: 200 # (optional) milliseconds to play in 'idle' before the transition is allowed
+  - from: jump
+    to: idle
+    on_end: true # wait for the end of the 'jump' animation
+  - to: idle # without 'from', the transition applies to any state
+    when: [!IsTrue reset]
+```
+"
+)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(
+        try_from = "dto::ControllerDto<T>",
+        into = "dto::ControllerDto<T>",
+        bound(
+            serialize = "T: Serialize + Clone",
+            deserialize = "T: Deserialize<'de>"
+        )
+    )
+)]
+pub struct Controller<T = usize> {
+    states: Vec<(String, Animation<T>)>,
+    /// Transitions, with the positions of their source (if any) and target states
+    transitions: Vec<(Option<usize>, usize, Transition)>,
+}
+
+/// A transition between two states of a [`Controller`]
+///
+/// The transition is taken when all its conditions are met.
+#[derive(Debug, Clone, PartialEq)]
+#[must_use]
+pub struct Transition {
+    from: Option<String>,
+    to: String,
+    conditions: Vec<Condition>,
+    on_end: bool,
+    exit_time: Duration,
+}
+
+/// A condition on the parameters of a [`ControllerState`], guarding a [`Transition`]
+///
+/// Parameters that were never set are `false` for booleans and `0.0` for floats.
+#[derive(Debug, Clone, PartialEq)]
+#[must_use]
+pub struct Condition(ConditionKind);
+
+#[derive(Debug, Clone, PartialEq)]
+enum ConditionKind {
+    IsTrue(String),
+    IsFalse(String),
+    GreaterThan(String, f64),
+    LessThan(String, f64),
+    Triggered(String),
+}
+
+/// Play-back state of a [`Controller`]
+///
+/// It holds the current state of the controller, the [`State`] of its animation,
+/// and the values of the parameters used by the transition conditions.
+///
+/// The current state is stored by its position in the controller,
+/// so that a controller state must always be played with the same controller.
+#[derive(Debug, Clone, Default)]
+pub struct ControllerState {
+    current: usize,
+    animation_state: State,
+    bools: HashMap<String, bool>,
+    floats: HashMap<String, f64>,
+    triggers: HashSet<String>,
+}
+
+impl Controller {
+    /// Create a new controller without any state
+    ///
+    /// For animations which frames are not indices in a sprite atlas, use [`Controller::default`]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<T> Controller<T> {
+    /// Add a named state playing the given animation
+    ///
+    /// The first state added is the initial state of the controller.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is already a state with the same name, or if the animation has no frame
+    ///
+    /// See [`try_with_state`](Self::try_with_state) for a fallible version
+    #[must_use]
+    pub fn with_state(self, name: impl Into<String>, animation: Animation<T>) -> Self {
+        self.try_with_state(name, animation)
+            .unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    /// Add a named state playing the given animation
+    ///
+    /// See [`with_state`](Self::with_state)
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ControllerError::DuplicateState`] if there is already a state with the same name,
+    /// or [`ControllerError::EmptyState`] if the animation has no frame
+    pub fn try_with_state(
+        mut self,
+        name: impl Into<String>,
+        animation: Animation<T>,
+    ) -> Result<Self, ControllerError> {
+        let name = name.into();
+        if self.position(&name).is_some() {
+            return Err(ControllerError::DuplicateState(name));
+        }
+        if !animation.has_frames() {
+            return Err(ControllerError::EmptyState(name));
+        }
+        self.states.push((name, animation));
+        Ok(self)
+    }
+
+    /// Add a transition
+    ///
+    /// Transitions from any state are checked first, and then the other transitions in the order they were added.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the transition refers to a state that has not been added
+    ///
+    /// See [`try_with_transition`](Self::try_with_transition) for a fallible version
+    #[must_use]
+    pub fn with_transition(self, transition: Transition) -> Self {
+        self.try_with_transition(transition)
+            .unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    /// Add a transition
+    ///
+    /// See [`with_transition`](Self::with_transition)
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ControllerError::UnknownState`] if the transition refers to a state that has not been added
+    pub fn try_with_transition(mut self, transition: Transition) -> Result<Self, ControllerError> {
+        let position = |name: &str| {
+            self.position(name)
+                .ok_or_else(|| ControllerError::UnknownState(name.into()))
+        };
+        let from = transition.from.as_deref().map(position).transpose()?;
+        let to = position(&transition.to)?;
+        let index = match from {
+            Some(_) => self.transitions.len(),
+            None => self
+                .transitions
+                .partition_point(|(from, _, _)| from.is_none()),
+        };
+        self.transitions.insert(index, (from, to, transition));
+        Ok(self)
+    }
+
+    /// Returns the animation of the given state, if there is a state with that name
+    #[must_use]
+    pub fn animation(&self, state: &str) -> Option<&Animation<T>> {
+        self.position(state)
+            .map(|position| &self.states[position].1)
+    }
+
+    fn position(&self, name: &str) -> Option<usize> {
+        self.states.iter().position(|(n, _)| n == name)
+    }
+}
+
+impl<T> Default for Controller<T> {
+    fn default() -> Self {
+        Self {
+            states: Vec::new(),
+            transitions: Vec::new(),
+        }
+    }
+}
+
+impl Transition {
+    /// Create a transition from the state named `from` to the state named `to`
+    pub fn new(from: impl Into<String>, to: impl Into<String>) -> Self {
+        Self {
+            from: Some(from.into()),
+            ..Self::from_any_state(to)
+        }
+    }
+
+    /// Create a transition from any other state to the state named `to`
+    pub fn from_any_state(to: impl Into<String>) -> Self {
+        Self {
+            from: None,
+            to: to.into(),
+            conditions: Vec::new(),
+            on_end: false,
+            exit_time: Duration::ZERO,
+        }
+    }
+
+    /// Only take the transition if the condition is met
+    ///
+    /// When there are multiple conditions, all of them must be met.
+    pub fn when(mut self, condition: Condition) -> Self {
+        self.conditions.push(condition);
+        self
+    }
+
+    /// Only take the transition once the animation of the current state has ended
+    pub fn on_end(mut self) -> Self {
+        self.on_end = true;
+        self
+    }
+
+    /// Only take the transition once the current state has been played for at least `exit_time`
+    ///
+    /// The time is measured like [`State::elapsed`]
+    pub fn with_exit_time(mut self, exit_time: Duration) -> Self {
+        self.exit_time = exit_time;
+        self
+    }
+}
+
+impl Condition {
+    /// The boolean parameter must be `true`
+    pub fn is_true(param: impl Into<String>) -> Self {
+        Self(ConditionKind::IsTrue(param.into()))
+    }
+
+    /// The boolean parameter must be `false`
+    pub fn is_false(param: impl Into<String>) -> Self {
+        Self(ConditionKind::IsFalse(param.into()))
+    }
+
+    /// The float parameter must be greater than `value`
+    pub fn greater_than(param: impl Into<String>, value: f64) -> Self {
+        Self(ConditionKind::GreaterThan(param.into(), value))
+    }
+
+    /// The float parameter must be less than `value`
+    pub fn less_than(param: impl Into<String>, value: f64) -> Self {
+        Self(ConditionKind::LessThan(param.into(), value))
+    }
+
+    /// The trigger must be set
+    ///
+    /// The trigger is consumed when the transition is taken.
+    pub fn triggered(param: impl Into<String>) -> Self {
+        Self(ConditionKind::Triggered(param.into()))
+    }
+}
+
+impl ControllerState {
+    /// Create a new state, in the initial state of the controller
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the name of the current state
+    ///
+    /// # Panics
+    ///
+    /// Panics if the controller has no state,
+    /// or if this state was played with another controller that has more states
+    #[must_use]
+    pub fn current_state<'a, T>(&self, controller: &'a Controller<T>) -> &'a str {
+        &self.current(controller).0
+    }
+
+    /// Returns the state of the animation being played
+    #[must_use]
+    pub fn animation_state(&self) -> &State {
+        &self.animation_state
+    }
+
+    /// Returns a mutable reference to the state of the animation being played
+    ///
+    /// This may be used to change the [speed](State::set_speed), which is preserved when the controller
+    /// changes state.
+    #[must_use]
+    pub fn animation_state_mut(&mut self) -> &mut State {
+        &mut self.animation_state
+    }
+
+    /// Returns the current frame index
+    #[must_use]
+    pub fn frame_index(&self) -> usize {
+        self.animation_state.frame_index()
+    }
+
+    /// Returns the value of the current frame in the animation of the current state
+    ///
+    /// See [`State::frame_value`]
+    ///
+    /// # Panics
+    ///
+    /// Panics if the controller has no state,
+    /// or if this state was played with another controller that has more states
+    #[must_use]
+    pub fn frame_value<'a, T>(&self, controller: &'a Controller<T>) -> &'a T {
+        self.animation_state
+            .frame_value(&self.current(controller).1)
+    }
+
+    /// Set the value of a boolean parameter
+    pub fn set_bool(&mut self, param: impl Into<String>, value: bool) {
+        self.bools.insert(param.into(), value);
+    }
+
+    /// Set the value of a float parameter
+    pub fn set_float(&mut self, param: impl Into<String>, value: f64) {
+        self.floats.insert(param.into(), value);
+    }
+
+    /// Set a trigger
+    ///
+    /// The trigger remains set until a transition that requires it is taken,
+    /// or until [`reset_trigger`](Self::reset_trigger) is called.
+    pub fn set_trigger(&mut self, param: impl Into<String>) {
+        self.triggers.insert(param.into());
+    }
+
+    /// Unset a trigger
+    pub fn reset_trigger(&mut self, param: &str) {
+        self.triggers.remove(param);
+    }
+
+    /// Update the animation of the current state, and then take the first transition which conditions are met
+    ///
+    /// When a transition is taken, the animation of the new state starts from its first frame,
+    /// and is played for the time left after the transition point:
+    /// the end of the animation for an [`on_end`](Transition::on_end) transition,
+    /// or the moment the [exit time](Transition::with_exit_time) is reached.
+    /// The transitions of the new state are then evaluated in turn,
+    /// up to one transition per transition of the controller in a single update.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the controller has no state,
+    /// or if this state was played with another controller that has more states
+    pub fn update<T: SpriteIndex>(&mut self, controller: &Controller<T>, delta: Duration) {
+        self.advance(controller, delta, &mut |_| ());
+    }
+
+    /// Update the controller, and report what happened to the animation of the current state
+    ///
+    /// See [`update`](Self::update) and [`State::update_with_events`]
+    ///
+    /// # Panics
+    ///
+    /// Panics if the controller has no state,
+    /// or if this state was played with another controller that has more states
+    pub fn update_with_events<'a, T: SpriteIndex>(
+        &mut self,
+        controller: &'a Controller<T>,
+        delta: Duration,
+        events: &mut impl Extend<Event<'a>>,
+    ) {
+        self.advance(controller, delta, &mut |event| events.extend(Some(event)));
+    }
+
+    fn advance<'a, T: SpriteIndex>(
+        &mut self,
+        controller: &'a Controller<T>,
+        mut delta: Duration,
+        on_event: &mut impl FnMut(Event<'a>),
+    ) {
+        for _ in 0..=controller.transitions.len() {
+            let animation = &self.current(controller).1;
+            let leftover = self
+                .animation_state
+                .update_with_leftover(animation, delta, on_event);
+            let transition = controller
+                .transitions
+                .iter()
+                .find(|(from, to, transition)| {
+                    from.map_or(*to != self.current, |from| from == self.current)
+                        && self.allows(transition)
+                });
+            let Some((_, to, transition)) = transition else {
+                return;
+            };
+            for condition in &transition.conditions {
+                if let ConditionKind::Triggered(param) = &condition.0 {
+                    self.triggers.remove(param);
+                }
+            }
+            delta = self.time_after_allowed(transition, delta, leftover);
+            self.current = *to;
+            self.animation_state.reset();
+        }
+        let animation = &self.current(controller).1;
+        self.animation_state.update_with(animation, delta, on_event);
+    }
+
+    /// Returns the name and animation of the current state
+    fn current<'a, T>(&self, controller: &'a Controller<T>) -> &'a (String, Animation<T>) {
+        assert!(!controller.states.is_empty(), "The controller has no state");
+        controller.states.get(self.current).unwrap_or_else(|| {
+            panic!(
+                "The controller has no state at position {}, the controller state was played with another controller",
+                self.current
+            )
+        })
+    }
+
+    /// Returns the part of `delta` played after `transition` became allowed
+    ///
+    /// `leftover` is the part of `delta` left over after the end of the animation.
+    /// The conditions are assumed to be met at the end of the update,
+    /// so that only the end of the animation and the exit time may leave time to the next state.
+    fn time_after_allowed(
+        &self,
+        transition: &Transition,
+        delta: Duration,
+        leftover: Duration,
+    ) -> Duration {
+        let after_end = transition.on_end.then_some(leftover);
+        let after_exit = (!transition.exit_time.is_zero()).then(|| {
+            let past = self
+                .animation_state
+                .elapsed()
+                .saturating_sub(transition.exit_time);
+            Duration::try_from_secs_f64(past.as_secs_f64() / self.animation_state.speed().abs())
+                .map_or(delta, |past| past.min(delta))
+        });
+        after_end
+            .into_iter()
+            .chain(after_exit)
+            .min()
+            .unwrap_or_default()
+    }
+
+    fn allows(&self, transition: &Transition) -> bool {
+        (!transition.on_end || self.animation_state.is_ended())
+            && self.animation_state.elapsed() >= transition.exit_time
+            && transition.conditions.iter().all(|c| self.is_met(c))
+    }
+
+    fn is_met(&self, condition: &Condition) -> bool {
+        let float = |param: &str| self.floats.get(param).copied().unwrap_or_default();
+        let bool = |param: &str| self.bools.get(param).copied().unwrap_or_default();
+        match &condition.0 {
+            ConditionKind::IsTrue(param) => bool(param),
+            ConditionKind::IsFalse(param) => !bool(param),
+            ConditionKind::GreaterThan(param, value) => float(param) > *value,
+            ConditionKind::LessThan(param, value) => float(param) < *value,
+            ConditionKind::Triggered(param) => self.triggers.contains(param),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FrameRate;
+
+    #[fixture]
+    fn frame_duration() -> Duration {
+        Duration::from_secs(1)
+    }
+
+    #[fixture]
+    fn controller() -> Controller {
+        let frame_rate = FrameRate::from_frame_duration(Duration::from_secs(1));
+        Controller::new()
+            .with_state("idle", Animation::from_indices(0..=1, frame_rate))
+            .with_state("run", Animation::from_indices(10..=11, frame_rate))
+            .with_state(
+                "attack",
+                Animation::from_indices(20..=21, frame_rate).once(),
+            )
+            .with_state("hurt", Animation::from_indices(30..=31, frame_rate).once())
+            .with_transition(Transition::new("idle", "run").when(Condition::is_true("running")))
+            .with_transition(Transition::new("run", "idle").when(Condition::is_false("running")))
+            .with_transition(Transition::new("idle", "attack").when(Condition::triggered("attack")))
+            .with_transition(Transition::new("attack", "idle").on_end())
+            .with_transition(
+                Transition::from_any_state("hurt").when(Condition::greater_than("damage", 0.0)),
+            )
+            .with_transition(
+                Transition::new("hurt", "idle")
+                    .when(Condition::less_than("damage", 1.0))
+                    .with_exit_time(Duration::from_secs(1)),
+            )
+    }
+
+    #[rstest]
+    fn starts_in_first_state(controller: Controller, frame_duration: Duration) {
+        let mut state = ControllerState::new();
+        state.update(&controller, frame_duration);
+        assert_eq!(state.current_state(&controller), "idle");
+        assert_eq!(state.frame_index(), 1);
+    }
+
+    #[rstest]
+    fn bool_parameter(controller: Controller, frame_duration: Duration) {
+        let mut state = ControllerState::new();
+        state.set_bool("running", true);
+        state.update(&controller, frame_duration);
+        assert_eq!(state.current_state(&controller), "run");
+        assert_eq!(state.frame_index(), 10);
+        state.set_bool("running", false);
+        state.update(&controller, frame_duration);
+        assert_eq!(state.current_state(&controller), "idle");
+    }
+
+    #[rstest]
+    fn reports_first_frame_of_new_state(controller: Controller, frame_duration: Duration) {
+        let mut state = ControllerState::new();
+        state.update(&controller, frame_duration);
+        state.set_bool("running", true);
+        let mut events = Vec::new();
+        state.update_with_events(&controller, frame_duration / 2, &mut events);
+        assert_eq!(
+            events.last(),
+            Some(&Event::FrameEntered {
+                position: 0,
+                index: Some(10)
+            })
+        );
+    }
+
+    #[rstest]
+    fn trigger_is_consumed(controller: Controller, frame_duration: Duration) {
+        let mut state = ControllerState::new();
+        state.set_trigger("attack");
+        state.update(&controller, frame_duration);
+        assert_eq!(state.current_state(&controller), "attack");
+        state.update(&controller, frame_duration * 3);
+        assert_eq!(state.current_state(&controller), "idle");
+        state.update(&controller, frame_duration);
+        assert_eq!(state.current_state(&controller), "idle");
+    }
+
+    #[rstest]
+    fn reset_trigger(controller: Controller, frame_duration: Duration) {
+        let mut state = ControllerState::new();
+        state.set_trigger("attack");
+        state.reset_trigger("attack");
+        state.update(&controller, frame_duration);
+        assert_eq!(state.current_state(&controller), "idle");
+    }
+
+    #[rstest]
+    fn waits_for_end(controller: Controller, frame_duration: Duration) {
+        let mut state = ControllerState::new();
+        state.set_trigger("attack");
+        state.update(&controller, frame_duration);
+        state.update(&controller, frame_duration);
+        assert_eq!(state.current_state(&controller), "attack");
+        assert_eq!(state.frame_index(), 21);
+        state.update(&controller, frame_duration);
+        assert_eq!(state.current_state(&controller), "idle");
+    }
+
+    #[rstest]
+    fn any_state_transition(controller: Controller, frame_duration: Duration) {
+        let mut state = ControllerState::new();
+        state.set_bool("running", true);
+        state.update(&controller, frame_duration);
+        state.set_float("damage", 2.0);
+        state.update(&controller, frame_duration);
+        assert_eq!(state.current_state(&controller), "hurt");
+    }
+
+    #[rstest]
+    fn any_state_transition_does_not_restart_current_state(
+        controller: Controller,
+        frame_duration: Duration,
+    ) {
+        let mut state = ControllerState::new();
+        state.set_float("damage", 2.0);
+        state.update(&controller, frame_duration);
+        state.update(&controller, frame_duration);
+        assert_eq!(state.current_state(&controller), "hurt");
+        assert_eq!(state.frame_index(), 31);
+    }
+
+    #[rstest]
+    fn any_state_transitions_have_priority(frame_duration: Duration) {
+        let animation =
+            Animation::from_indices(0..=1, FrameRate::from_frame_duration(frame_duration));
+        let controller = Controller::new()
+            .with_state("a", animation.clone())
+            .with_state("b", animation.clone())
+            .with_state("c", animation)
+            .with_transition(Transition::new("a", "b"))
+            .with_transition(Transition::from_any_state("c"));
+        let mut state = ControllerState::new();
+        state.update(&controller, frame_duration);
+        assert_eq!(state.current_state(&controller), "c");
+    }
+
+    #[rstest]
+    fn exit_time(controller: Controller, frame_duration: Duration) {
+        let mut state = ControllerState::new();
+        state.set_float("damage", 2.0);
+        state.update(&controller, frame_duration);
+        state.set_float("damage", 0.0);
+        state.update(&controller, frame_duration / 2);
+        assert_eq!(state.current_state(&controller), "hurt");
+        state.update(&controller, frame_duration / 2);
+        assert_eq!(state.current_state(&controller), "idle");
+    }
+
+    #[rstest]
+    fn carries_time_left_after_the_end(controller: Controller, frame_duration: Duration) {
+        let mut state = ControllerState::new();
+        state.set_trigger("attack");
+        state.update(&controller, frame_duration);
+        state.update(&controller, frame_duration * 3);
+        assert_eq!(state.current_state(&controller), "idle");
+        assert_eq!(state.frame_index(), 1);
+    }
+
+    #[rstest]
+    fn carries_time_left_after_the_exit_time(controller: Controller, frame_duration: Duration) {
+        let mut state = ControllerState::new();
+        state.set_float("damage", 2.0);
+        state.update(&controller, frame_duration);
+        state.set_float("damage", 0.0);
+        state.update(&controller, frame_duration * 2);
+        assert_eq!(state.current_state(&controller), "idle");
+        assert_eq!(state.frame_index(), 1);
+    }
+
+    #[rstest]
+    fn chains_transitions_in_one_update(frame_duration: Duration) {
+        let frame_rate = FrameRate::from_frame_duration(frame_duration);
+        let controller = Controller::new()
+            .with_state("a", Animation::from_indices(0..=1, frame_rate).once())
+            .with_state("b", Animation::from_indices(10..=11, frame_rate))
+            .with_state("c", Animation::from_indices(20..=21, frame_rate))
+            .with_transition(Transition::new("a", "b").on_end())
+            .with_transition(Transition::new("b", "c"));
+        let mut state = ControllerState::new();
+        let mut events = Vec::new();
+        state.update_with_events(&controller, frame_duration * 2, &mut events);
+        assert_eq!(state.current_state(&controller), "c");
+        assert_eq!(state.frame_index(), 20);
+        assert!(events.contains(&Event::FrameEntered {
+            position: 0,
+            index: Some(10)
+        }));
+    }
+
+    #[rstest]
+    fn bounds_the_transitions_taken_in_one_update(frame_duration: Duration) {
+        let animation =
+            Animation::from_indices(0..=1, FrameRate::from_frame_duration(frame_duration));
+        let controller = Controller::new()
+            .with_state("a", animation.clone())
+            .with_state("b", animation)
+            .with_transition(Transition::new("a", "b"))
+            .with_transition(Transition::new("b", "a"));
+        let mut state = ControllerState::new();
+        state.update(&controller, frame_duration);
+        assert_eq!(state.current_state(&controller), "b");
+    }
+
+    #[rstest]
+    fn preserves_speed(controller: Controller, frame_duration: Duration) {
+        let mut state = ControllerState::new();
+        state.animation_state_mut().set_speed(2.0);
+        state.set_bool("running", true);
+        state.update(&controller, frame_duration);
+        state.update(&controller, frame_duration);
+        assert_eq!(state.current_state(&controller), "run");
+        assert!((state.animation_state().speed() - 2.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn plays_any_frame_value() {
+        let frame_rate = FrameRate::from_fps(10.0);
+        let controller = Controller::default()
+            .with_state(
+                "idle",
+                Animation::from_values(["idle 0", "idle 1"], frame_rate),
+            )
+            .with_state(
+                "run",
+                Animation::from_values(["run 0", "run 1"], frame_rate),
+            )
+            .with_transition(Transition::new("idle", "run").when(Condition::is_true("running")));
+        let mut state = ControllerState::new();
+        state.update(&controller, Duration::from_millis(150));
+        assert_eq!(state.frame_value(&controller), &"idle 1");
+        state.set_bool("running", true);
+        state.update(&controller, Duration::from_millis(50));
+        assert_eq!(state.current_state(&controller), "run");
+        assert_eq!(state.frame_value(&controller), &"run 0");
+    }
+
+    #[test]
+    #[should_panic(expected = "unknown state")]
+    fn panics_for_unknown_state() {
+        let _ = Controller::new().with_transition(Transition::from_any_state("nope"));
+    }
+
+    #[rstest]
+    #[should_panic(expected = "played with another controller")]
+    fn panics_for_state_of_another_controller(controller: Controller, frame_duration: Duration) {
+        let mut state = ControllerState::new();
+        state.set_bool("running", true);
+        state.update(&controller, frame_duration);
+        assert_eq!(state.current_state(&controller), "run");
+        let other =
+            Controller::new().with_state("idle", controller.animation("idle").unwrap().clone());
+        let _ = state.current_state(&other);
+    }
+
+    #[test]
+    #[should_panic(expected = "duplicate state")]
+    fn panics_for_duplicate_state() {
+        let animation = Animation::from_indices(0..=1, FrameRate::from_fps(1.0));
+        let _ = Controller::new()
+            .with_state("a", animation.clone())
+            .with_state("a", animation);
+    }
+
+    #[test]
+    fn try_builders_return_errors() {
+        let animation = Animation::from_indices(0..=1, FrameRate::from_fps(10.0));
+        let controller = Controller::new()
+            .try_with_state("idle", animation.clone())
+            .unwrap();
+        assert_eq!(
+            controller.clone().try_with_state("idle", animation),
+            Err(ControllerError::DuplicateState("idle".into()))
+        );
+        assert_eq!(
+            controller
+                .clone()
+                .try_with_state("empty", Animation::from_frames([])),
+            Err(ControllerError::EmptyState("empty".into()))
+        );
+        assert_eq!(
+            controller.try_with_transition(Transition::new("idle", "run")),
+            Err(ControllerError::UnknownState("run".into()))
+        );
+    }
+}
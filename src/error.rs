@@ -0,0 +1,134 @@
+use std::fmt::{self, Display, Formatter};
+
+use crate::ValidationReport;
+
+/// Error returned when creating or deserializing an invalid frame, frame-rate, animation or animation set
+///
+/// It is returned by the `try_*` constructors (like [`Frame::try_new`](crate::Frame::try_new)
+/// or [`Animation::try_from_indices`](crate::Animation::try_from_indices)),
+/// and by the deserialization (with the `serde` feature).
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum Error {
+    /// The animation has no frame
+    NoFrame,
+    /// A frame has a zero duration
+    ZeroDuration,
+    /// The FPS is negative, zero or not finite
+    InvalidFps(f64),
+    /// There are more than a billion ticks per second, so that a tick would be shorter than a nanosecond
+    InvalidTickRate(u32),
+    /// Ticks are used without defining the `tick_rate`
+    MissingTickRate,
+    /// More than one of `fps`, `frame_duration`, `total_duration` and `frame_ticks` are defined
+    IncompatibleFrameRate,
+    /// The loop of a [`sustain`](crate::Animation::sustain) animation is empty
+    EmptyLoop {
+        /// Position of the first frame of the loop
+        loop_start: usize,
+        /// Position of the last frame of the loop
+        loop_end: usize,
+    },
+    /// The deserialized animation did not pass the [validation](crate::Animation::validate)
+    ///
+    /// The report lists all the problems found, including the warnings.
+    /// A deserialized animation with warnings but no error is returned without its report.
+    Invalid(ValidationReport),
+    /// The named animation of an [`AnimationSet`](crate::AnimationSet) is invalid
+    InvalidAnimation {
+        /// Name of the animation in the set
+        name: String,
+        /// Reason why the animation is invalid
+        source: Box<Error>,
+    },
+    /// The [next](crate::Animation::next) animation of a deserialized animation is not in its
+    /// [`AnimationSet`](crate::AnimationSet)
+    UnknownAnimation(String),
+}
+
+/// Error returned when building an invalid [`Controller`](crate::Controller)
+///
+/// It is returned by [`Controller::try_with_state`](crate::Controller::try_with_state)
+/// and [`Controller::try_with_transition`](crate::Controller::try_with_transition),
+/// and by the deserialization of a controller (with the `serde` feature).
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum ControllerError {
+    /// There is already a state with that name in the controller
+    DuplicateState(String),
+    /// A transition refers to a state that is not in the controller
+    UnknownState(String),
+    /// The animation of the named controller state has no frame
+    EmptyState(String),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::NoFrame => write!(f, "the animation has no frame"),
+            Error::ZeroDuration => write!(f, "zero-duration is invalid for animation frame"),
+            Error::InvalidFps(fps) => write!(f, "invalid fps: {fps}, must be finite and > 0"),
+            Error::InvalidTickRate(hz) => {
+                write!(f, "invalid tick rate: {hz}, must be at most 1000000000")
+            }
+            Error::MissingTickRate => write!(f, "the tick_rate must be defined to use ticks"),
+            Error::IncompatibleFrameRate => write!(
+                f,
+                "only one of fps, frame_duration, total_duration and frame_ticks may be defined"
+            ),
+            Error::EmptyLoop {
+                loop_start,
+                loop_end,
+            } => write!(f, "invalid loop: {loop_start}..={loop_end} is empty"),
+            Error::Invalid(report) => write!(f, "invalid animation: {report}"),
+            Error::InvalidAnimation { name, source } => {
+                write!(f, "invalid animation {name:?}: {source}")
+            }
+            Error::UnknownAnimation(name) => write!(f, "unknown next animation: {name:?}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::InvalidAnimation { source, .. } => Some(source.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl Display for ControllerError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ControllerError::DuplicateState(name) => write!(f, "duplicate state: {name:?}"),
+            ControllerError::UnknownState(name) => write!(f, "unknown state: {name:?}"),
+            ControllerError::EmptyState(name) => {
+                write!(f, "the animation of state {name:?} has no frame")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ControllerError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn invalid_animation_has_source() {
+        let error = Error::InvalidAnimation {
+            name: "idle".into(),
+            source: Box::new(Error::ZeroDuration),
+        };
+        assert_eq!(
+            error.to_string(),
+            "invalid animation \"idle\": zero-duration is invalid for animation frame"
+        );
+        assert_eq!(
+            std::error::Error::source(&error).map(ToString::to_string),
+            Some(Error::ZeroDuration.to_string())
+        );
+    }
+}
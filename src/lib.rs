@@ -46,8 +46,21 @@
 #[macro_use]
 extern crate rstest;
 
-pub use animation::{Animation, Frame, FrameRate};
-pub use state::State;
+#[cfg(feature = "aseprite")]
+pub use animation::AsepriteError;
+pub use animation::{
+    Animation, AnimationSet, AtlasIndex, Frame, FrameMetadata, FrameRate, MissingAnimation, Mode,
+    Problem, Sample, Severity, SpriteIndex, TickRate, Validation, ValidationReport,
+};
+pub use controller::{Condition, Controller, ControllerState, Transition};
+pub use error::{ControllerError, Error};
+pub use state::{
+    AnimationQueue, Crossfade, CrossfadeOutcome, Event, RebindPolicy, State, UpdateOutcome,
+};
 
 mod animation;
+#[cfg(feature = "bevy")]
+pub mod bevy;
+mod controller;
+mod error;
 mod state;
@@ -0,0 +1,339 @@
+use std::time::Duration;
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+use super::rebind::Binding;
+use crate::{Animation, RebindPolicy, SpriteIndex, State};
+
+impl State {
+    /// Update all the `states` with the same animation, and report the positions of the states which frame index changed
+    ///
+    /// The positions are pushed into `changed` in increasing order,
+    /// so that a renderer may only upload the sprites that changed.
+    /// A state that is updated for the first time is always reported, since its first frame is entered.
+    ///
+    /// The states are updated like with [`update`](Self::update), but what is shared by the states is computed once,
+    /// and no [`Event`](crate::Event) nor [`UpdateOutcome`](crate::UpdateOutcome) is built for each of them.
+    ///
+    /// With the `rayon` feature, `par_update_all` updates the states in parallel.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use std::time::Duration;
+    /// use benimator::*;
+    ///
+    /// let animation = Animation::from_indices(0..=3, FrameRate::from_fps(10.0));
+    /// let mut states = vec![State::new(); 3];
+    /// let mut changed = Vec::new();
+    ///
+    /// State::update_all(&mut states, &animation, Duration::from_millis(50), &mut changed);
+    /// assert_eq!(changed, vec![0, 1, 2]);
+    ///
+    /// changed.clear();
+    /// states[1].set_speed(3.0);
+    /// State::update_all(&mut states, &animation, Duration::from_millis(20), &mut changed);
+    /// assert_eq!(changed, vec![1]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the animation has no frame
+    pub fn update_all<T: SpriteIndex>(
+        states: &mut [State],
+        animation: &Animation<T>,
+        delta: Duration,
+        changed: &mut impl Extend<usize>,
+    ) {
+        assert!(animation.has_frames(), "The animation has no frame");
+        let binding = Binding::of(animation);
+        changed.extend(
+            states
+                .iter_mut()
+                .enumerate()
+                .filter_map(|(position, state)| {
+                    state
+                        .update_index(animation, binding, delta)
+                        .then_some(position)
+                }),
+        );
+    }
+
+    /// Update each state with its own animation among the shared `animations`,
+    /// and report the positions of the states which frame index changed
+    ///
+    /// The state at position `i` in `states` is updated with the animation at position `animation_ids[i]` in
+    /// `animations`, so that many states may share a few animations.
+    ///
+    /// See [`update_all`](Self::update_all)
+    ///
+    /// # Panics
+    ///
+    /// Panics if `states` and `animation_ids` don't have the same length,
+    /// if an animation id is not a position in `animations`, or if one of the `animations` has no frame
+    pub fn update_all_with<T: SpriteIndex>(
+        states: &mut [State],
+        animations: &[Animation<T>],
+        animation_ids: &[usize],
+        delta: Duration,
+        changed: &mut impl Extend<usize>,
+    ) {
+        assert_one_id_per_state(states, animations, animation_ids);
+        changed.extend(states.iter_mut().zip(animation_ids).enumerate().filter_map(
+            |(position, (state, &id))| {
+                let animation = &animations[id];
+                state
+                    .update_index(animation, Binding::of(animation), delta)
+                    .then_some(position)
+            },
+        ));
+    }
+
+    /// Update all the `states` in parallel with the same animation,
+    /// and report the positions of the states which frame index changed
+    ///
+    /// See [`update_all`](Self::update_all)
+    ///
+    /// # Panics
+    ///
+    /// Panics if the animation has no frame
+    #[cfg(feature = "rayon")]
+    pub fn par_update_all<T: SpriteIndex + Sync>(
+        states: &mut [State],
+        animation: &Animation<T>,
+        delta: Duration,
+        changed: &mut impl ParallelExtend<usize>,
+    ) {
+        assert!(animation.has_frames(), "The animation has no frame");
+        let binding = Binding::of(animation);
+        changed.par_extend(
+            states
+                .par_iter_mut()
+                .enumerate()
+                .filter_map(|(position, state)| {
+                    state
+                        .update_index(animation, binding, delta)
+                        .then_some(position)
+                }),
+        );
+    }
+
+    /// Update each state in parallel with its own animation among the shared `animations`,
+    /// and report the positions of the states which frame index changed
+    ///
+    /// See [`update_all_with`](Self::update_all_with)
+    ///
+    /// # Panics
+    ///
+    /// Panics if `states` and `animation_ids` don't have the same length,
+    /// if an animation id is not a position in `animations`, or if one of the `animations` has no frame
+    #[cfg(feature = "rayon")]
+    pub fn par_update_all_with<T: SpriteIndex + Sync>(
+        states: &mut [State],
+        animations: &[Animation<T>],
+        animation_ids: &[usize],
+        delta: Duration,
+        changed: &mut impl ParallelExtend<usize>,
+    ) {
+        assert_one_id_per_state(states, animations, animation_ids);
+        changed.par_extend(
+            states
+                .par_iter_mut()
+                .zip(animation_ids)
+                .enumerate()
+                .filter_map(|(position, (state, &id))| {
+                    let animation = &animations[id];
+                    state
+                        .update_index(animation, Binding::of(animation), delta)
+                        .then_some(position)
+                }),
+        );
+    }
+
+    /// Update the state like [`update`](Self::update), and returns true if the sprite index changed
+    ///
+    /// Nothing is reported, and `binding` is the [`Binding::of`] the animation, computed once for many states.
+    fn update_index<T: SpriteIndex>(
+        &mut self,
+        animation: &Animation<T>,
+        binding: Binding,
+        delta: Duration,
+    ) -> bool {
+        if !self
+            .binding
+            .map_or(true, |previous| previous.same_animation(&binding))
+        {
+            self.rebind(animation, RebindPolicy::Clamp);
+        }
+        let previous = (self.is_started, self.sprite_frame_index);
+        self.move_play_head(animation, delta, &mut |_| ());
+        self.binding = Some(binding.at(animation.frame_start(self.position(animation))));
+        previous != (true, self.sprite_frame_index)
+    }
+}
+
+/// Check the arguments of the update of states with their own animation
+fn assert_one_id_per_state<T>(
+    states: &[State],
+    animations: &[Animation<T>],
+    animation_ids: &[usize],
+) {
+    assert_eq!(
+        states.len(),
+        animation_ids.len(),
+        "There must be one animation id per state"
+    );
+    assert!(
+        animations.iter().all(Animation::has_frames),
+        "An animation has no frame"
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FrameRate;
+
+    fn animations() -> Vec<Animation> {
+        vec![
+            Animation::from_indices(0..=3, FrameRate::from_fps(10.0)),
+            Animation::from_indices([4], FrameRate::from_fps(10.0)),
+        ]
+    }
+
+    #[test]
+    fn update_all_reports_changed_states() {
+        let animation = &animations()[0];
+        let mut states = vec![State::new(); 3];
+        states[2].seek(animation, Duration::from_millis(50));
+        let mut changed = Vec::new();
+        State::update_all(
+            &mut states,
+            animation,
+            Duration::from_millis(60),
+            &mut changed,
+        );
+        assert_eq!(changed, vec![0, 1, 2]);
+        assert_eq!(states[0].frame_index(), 0);
+        assert_eq!(states[2].frame_index(), 1);
+
+        changed.clear();
+        State::update_all(
+            &mut states,
+            animation,
+            Duration::from_millis(60),
+            &mut changed,
+        );
+        assert_eq!(changed, vec![0, 1]);
+    }
+
+    #[test]
+    fn update_all_with_shared_animations() {
+        let animations = animations();
+        let mut states = vec![State::new(); 4];
+        let ids = [0, 1, 1, 0];
+        let mut changed = Vec::new();
+        State::update_all_with(&mut states, &animations, &ids, Duration::ZERO, &mut changed);
+        assert_eq!(changed, vec![0, 1, 2, 3]);
+
+        changed.clear();
+        State::update_all_with(
+            &mut states,
+            &animations,
+            &ids,
+            Duration::from_millis(100),
+            &mut changed,
+        );
+        assert_eq!(changed, vec![0, 3]);
+        assert_eq!(states[1].frame_index(), 4);
+    }
+
+    #[test]
+    #[should_panic(expected = "one animation id per state")]
+    fn update_all_with_requires_one_id_per_state() {
+        State::update_all_with(
+            &mut [State::new()],
+            &animations(),
+            &[],
+            Duration::ZERO,
+            &mut Vec::new(),
+        );
+    }
+
+    #[rstest]
+    #[case::forward(1.0)]
+    #[case::fast(3.0)]
+    #[case::slow(0.3)]
+    #[case::backward(-1.0)]
+    fn update_all_is_the_same_as_update(#[case] speed: f64) {
+        let animation = Animation::from_indices([0, 1, 1, 2], FrameRate::from_fps(10.0))
+            .ping_pong()
+            .with_marker(1, "a");
+        let mut states: Vec<State> = (0..40)
+            .map(|i| {
+                let mut state = State::new();
+                state.set_speed(speed);
+                if i % 3 > 0 {
+                    state.seek(&animation, Duration::from_millis(i * 35));
+                }
+                state
+            })
+            .collect();
+        let mut updated = states.clone();
+        let mut changed = Vec::new();
+        for _ in 0..10 {
+            let delta = Duration::from_millis(70);
+            let expected: Vec<usize> = (0..states.len())
+                .filter(|&i| states[i].update(&animation, delta).index_changed())
+                .collect();
+            changed.clear();
+            State::update_all(&mut updated, &animation, delta, &mut changed);
+            assert_eq!(changed, expected);
+            assert_eq!(updated, states);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "has no frame")]
+    fn update_all_with_requires_animations_with_frames() {
+        let animations = vec![animations().remove(0), Animation::from_frames([])];
+        State::update_all_with(
+            &mut [State::new()],
+            &animations,
+            &[0],
+            Duration::ZERO,
+            &mut Vec::new(),
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "rayon")]
+    fn parallel_updates_are_the_same() {
+        let animations = animations();
+        let ids: Vec<usize> = (0..1000).map(|i| i % 2).collect();
+        let mut states: Vec<State> = (0..1000)
+            .map(|i| {
+                let mut state = State::new();
+                let elapsed = Duration::from_millis(u64::try_from(i % 400).unwrap());
+                state.seek(&animations[ids[i]], elapsed);
+                state
+            })
+            .collect();
+        let mut sequential = states.clone();
+        let (mut expected, mut changed) = (Vec::new(), Vec::new());
+        let delta = Duration::from_millis(70);
+        State::update_all_with(&mut sequential, &animations, &ids, delta, &mut expected);
+        State::par_update_all_with(&mut states, &animations, &ids, delta, &mut changed);
+        assert_eq!(changed, expected);
+        assert_eq!(states, sequential);
+
+        let mut states = vec![State::new(); 1000];
+        let mut sequential = states.clone();
+        let (mut expected, mut changed) = (Vec::new(), Vec::new());
+        State::update_all(&mut sequential, &animations[0], delta, &mut expected);
+        State::par_update_all(&mut states, &animations[0], delta, &mut changed);
+        assert_eq!(changed, expected);
+        assert_eq!(states, sequential);
+    }
+}
@@ -0,0 +1,276 @@
+use std::time::Duration;
+
+use crate::{Animation, SpriteIndex, State};
+
+/// Blend between an outgoing animation and the animation that replaces it
+///
+/// A crossfade is started with [`State::crossfade`], which keeps the state to play the outgoing animation,
+/// and starts a new state to play the incoming animation from its first frame.
+/// Both animations are then updated side by side by [`Crossfade::update`], whatever their [`Mode`](crate::Mode).
+///
+/// The [`weight`](Self::weight) of the incoming animation goes linearly from `0.0` to `1.0`
+/// over the duration of the crossfade, after which the crossfade [is complete](Self::is_complete)
+/// and the incoming state may be taken back with [`Crossfade::into_incoming_state`].
+///
+/// # Example
+///
+/// ```
+/// # use std::time::Duration;
+/// use benimator::*;
+///
+/// let walk = Animation::from_indices(0..=3, FrameRate::from_fps(10.0));
+/// let run = Animation::from_indices(4..=7, FrameRate::from_fps(10.0));
+/// let mut state = State::new();
+/// state.update(&walk, Duration::from_millis(250));
+///
+/// let mut crossfade = state.crossfade(Duration::from_millis(200));
+/// let outcome = crossfade.update(&walk, &run, Duration::from_millis(100));
+///
+/// assert_eq!(outcome.outgoing_index(), 3);
+/// assert_eq!(outcome.incoming_index(), 5);
+/// assert!((outcome.weight() - 0.5).abs() < f32::EPSILON);
+/// assert!(!outcome.just_completed());
+///
+/// let outcome = crossfade.update(&walk, &run, Duration::from_millis(100));
+/// assert!(outcome.just_completed());
+///
+/// let state = crossfade.into_incoming_state();
+/// assert_eq!(state.frame_index(), 6);
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Crossfade {
+    outgoing: State,
+    incoming: State,
+    elapsed: Duration,
+    duration: Duration,
+}
+
+/// Frame indices and blend weight of a [`Crossfade`], returned by [`Crossfade::update`]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct CrossfadeOutcome {
+    outgoing_index: usize,
+    incoming_index: usize,
+    weight: f32,
+    just_completed: bool,
+}
+
+impl State {
+    /// Start a crossfade of the given duration, from the animation played so far to the next one
+    ///
+    /// This state is moved into the returned [`Crossfade`], to keep playing the outgoing animation,
+    /// and a [reset](Self::reset) copy of it plays the incoming animation, so that it keeps the same speed.
+    /// A zero `duration` returns a crossfade that is already complete.
+    #[must_use]
+    pub fn crossfade(self, duration: Duration) -> Crossfade {
+        let mut incoming = self.clone();
+        incoming.reset();
+        Crossfade {
+            outgoing: self,
+            incoming,
+            elapsed: Duration::ZERO,
+            duration,
+        }
+    }
+}
+
+impl Crossfade {
+    /// Create a crossfade of the given duration, from the `outgoing` state to the `incoming` state
+    ///
+    /// See [`State::crossfade`]
+    #[must_use]
+    pub fn new(outgoing: State, incoming: State, duration: Duration) -> Self {
+        Self {
+            outgoing,
+            incoming,
+            elapsed: Duration::ZERO,
+            duration,
+        }
+    }
+
+    /// Update both states, move the crossfade forward, and returns the frame indices and the blend weight
+    ///
+    /// The crossfade is measured in real time, whatever the [speed](State::speed) of the states.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the outgoing or the incoming animation has no frame
+    pub fn update<O: SpriteIndex, I: SpriteIndex>(
+        &mut self,
+        outgoing: &Animation<O>,
+        incoming: &Animation<I>,
+        delta: Duration,
+    ) -> CrossfadeOutcome {
+        let was_complete = self.is_complete();
+        self.outgoing.update(outgoing, delta);
+        self.incoming.update(incoming, delta);
+        self.elapsed = self.elapsed.saturating_add(delta).min(self.duration);
+        CrossfadeOutcome {
+            outgoing_index: self.outgoing.frame_index(),
+            incoming_index: self.incoming.frame_index(),
+            weight: self.weight(),
+            just_completed: !was_complete && self.is_complete(),
+        }
+    }
+
+    /// Returns the state of the outgoing animation
+    #[must_use]
+    pub fn outgoing_state(&self) -> &State {
+        &self.outgoing
+    }
+
+    /// Returns the state of the incoming animation
+    #[must_use]
+    pub fn incoming_state(&self) -> &State {
+        &self.incoming
+    }
+
+    /// Returns the state of the incoming animation, to keep playing it once the crossfade is complete
+    #[must_use]
+    pub fn into_incoming_state(self) -> State {
+        self.incoming
+    }
+
+    /// Returns the index of the current frame of the outgoing animation
+    #[must_use]
+    pub fn outgoing_index(&self) -> usize {
+        self.outgoing.frame_index()
+    }
+
+    /// Returns the index of the current frame of the incoming animation
+    #[must_use]
+    pub fn incoming_index(&self) -> usize {
+        self.incoming.frame_index()
+    }
+
+    /// Returns the weight of the incoming animation, between `0.0` and `1.0`
+    ///
+    /// The weight of the outgoing animation is `1.0 - weight`.
+    #[must_use]
+    pub fn weight(&self) -> f32 {
+        if self.duration.is_zero() {
+            return 1.0;
+        }
+        #[allow(clippy::cast_possible_truncation)]
+        let weight = (self.elapsed.as_secs_f64() / self.duration.as_secs_f64()) as f32;
+        weight
+    }
+
+    /// Returns the time elapsed since the start of the crossfade
+    #[must_use]
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// Returns the duration of the crossfade
+    #[must_use]
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    /// Returns true if the crossfade is complete, so that only the incoming animation is visible
+    #[must_use]
+    pub fn is_complete(&self) -> bool {
+        self.elapsed >= self.duration
+    }
+}
+
+impl CrossfadeOutcome {
+    /// Returns the index of the current frame of the outgoing animation
+    #[must_use]
+    pub fn outgoing_index(&self) -> usize {
+        self.outgoing_index
+    }
+
+    /// Returns the index of the current frame of the incoming animation
+    #[must_use]
+    pub fn incoming_index(&self) -> usize {
+        self.incoming_index
+    }
+
+    /// Returns the weight of the incoming animation, between `0.0` and `1.0`
+    ///
+    /// The weight of the outgoing animation is `1.0 - weight`.
+    #[must_use]
+    pub fn weight(&self) -> f32 {
+        self.weight
+    }
+
+    /// Returns true if the crossfade completed during this update
+    #[must_use]
+    pub fn just_completed(&self) -> bool {
+        self.just_completed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FrameRate;
+
+    fn walk() -> Animation {
+        Animation::from_indices(0..=3, FrameRate::from_fps(10.0))
+    }
+
+    fn run() -> Animation {
+        Animation::from_indices(4..=7, FrameRate::from_fps(10.0))
+    }
+
+    #[test]
+    fn starts_from_the_current_state() {
+        let mut state = State::new();
+        state.set_speed(2.0);
+        state.update(&walk(), Duration::from_millis(100));
+        let crossfade = state.crossfade(Duration::from_secs(1));
+        assert_eq!(crossfade.outgoing_index(), 2);
+        assert!(crossfade.weight().abs() < f32::EPSILON);
+        assert_eq!(crossfade.incoming_index(), 0);
+        assert!((crossfade.incoming_state().speed() - 2.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn advances_both_states() {
+        let mut state = State::new();
+        state.update(&walk(), Duration::from_millis(100));
+        let mut crossfade = state.crossfade(Duration::from_secs(1));
+        let outcome = crossfade.update(&walk(), &run(), Duration::from_millis(250));
+        assert_eq!(outcome.outgoing_index(), 3);
+        assert_eq!(outcome.incoming_index(), 6);
+        assert!((outcome.weight() - 0.25).abs() < f32::EPSILON);
+        assert_eq!(outcome.outgoing_index(), crossfade.outgoing_index());
+        assert_eq!(outcome.incoming_index(), crossfade.incoming_index());
+    }
+
+    #[test]
+    fn reports_completion_once() {
+        let mut crossfade = Crossfade::new(State::new(), State::new(), Duration::from_millis(300));
+        assert!(!crossfade
+            .update(&walk(), &run(), Duration::from_millis(200))
+            .just_completed());
+        assert!(crossfade
+            .update(&walk(), &run(), Duration::from_millis(200))
+            .just_completed());
+        let outcome = crossfade.update(&walk(), &run(), Duration::from_millis(200));
+        assert!(!outcome.just_completed());
+        assert!(crossfade.is_complete());
+        assert!((outcome.weight() - 1.0).abs() < f32::EPSILON);
+        assert_eq!(crossfade.elapsed(), Duration::from_millis(300));
+    }
+
+    #[test]
+    fn animations_keep_their_mode() {
+        let mut crossfade = Crossfade::new(State::new(), State::new(), Duration::from_secs(1));
+        crossfade.update(&walk().once(), &run(), Duration::from_millis(800));
+        assert!(crossfade.outgoing_state().is_ended());
+        assert_eq!(crossfade.outgoing_index(), 3);
+        assert!(!crossfade.incoming_state().is_ended());
+        assert_eq!(crossfade.incoming_index(), 4);
+        assert!(!crossfade.is_complete());
+    }
+
+    #[test]
+    fn zero_duration_is_complete() {
+        let crossfade = State::new().crossfade(Duration::ZERO);
+        assert!(crossfade.is_complete());
+        assert!((crossfade.weight() - 1.0).abs() < f32::EPSILON);
+    }
+}
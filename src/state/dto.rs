@@ -0,0 +1,137 @@
+use std::{
+    fmt::{self, Display, Formatter},
+    time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::State;
+
+/// Version of the serialization format of the state
+///
+/// It must be incremented, and the previous versions still supported for deserialization,
+/// whenever the format changes.
+const VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+#[allow(clippy::struct_excessive_bools)]
+pub(super) struct StateDto {
+    version: u32,
+    frame_position: usize,
+    frame_index: usize,
+    elapsed_in_frame: Duration,
+    elapsed: Duration,
+    going_backward: bool,
+    ended: bool,
+    started: bool,
+    speed: f64,
+    loops: u32,
+    released: bool,
+}
+
+/// Reason why a deserialized state is invalid
+#[derive(Debug)]
+pub(super) enum StateDtoError {
+    /// The version of the format is not supported
+    UnsupportedVersion(u32),
+    /// The speed is not finite
+    InvalidSpeed(f64),
+}
+
+impl From<State> for StateDto {
+    fn from(state: State) -> Self {
+        Self {
+            version: VERSION,
+            frame_position: state.animation_frame_index,
+            frame_index: state.sprite_frame_index,
+            elapsed_in_frame: state.elapsed_in_frame,
+            elapsed: state.elapsed,
+            going_backward: state.going_backward,
+            ended: state.is_ended,
+            started: state.is_started,
+            speed: state.speed,
+            loops: state.loops,
+            released: state.is_released,
+        }
+    }
+}
+
+impl TryFrom<StateDto> for State {
+    type Error = StateDtoError;
+
+    fn try_from(state: StateDto) -> Result<Self, Self::Error> {
+        if state.version != VERSION {
+            return Err(StateDtoError::UnsupportedVersion(state.version));
+        }
+        if !state.speed.is_finite() {
+            return Err(StateDtoError::InvalidSpeed(state.speed));
+        }
+        Ok(Self {
+            animation_frame_index: state.frame_position,
+            sprite_frame_index: state.frame_index,
+            elapsed_in_frame: state.elapsed_in_frame,
+            elapsed: state.elapsed,
+            going_backward: state.going_backward,
+            is_ended: state.ended,
+            is_started: state.started,
+            speed: state.speed,
+            loops: state.loops,
+            is_released: state.released,
+            binding: None,
+        })
+    }
+}
+
+impl Display for StateDtoError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            StateDtoError::UnsupportedVersion(version) => {
+                write!(f, "unsupported state format version: {version}")
+            }
+            StateDtoError::InvalidSpeed(speed) => {
+                write!(f, "invalid speed: {speed}, must be finite")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Animation, FrameRate};
+
+    fn state() -> State {
+        let animation = Animation::from_indices(0..=3, FrameRate::from_fps(10.0)).ping_pong();
+        let mut state = State::new();
+        state.set_speed(1.5);
+        state.update(&animation, Duration::from_millis(550));
+        state
+    }
+
+    #[test]
+    fn round_trip() {
+        let state = state();
+        let json = serde_json::to_string(&state).unwrap();
+        assert_eq!(serde_json::from_str::<State>(&json).unwrap(), state);
+        let yaml = serde_yaml::to_string(&state).unwrap();
+        assert_eq!(serde_yaml::from_str::<State>(&yaml).unwrap(), state);
+    }
+
+    #[test]
+    fn format_is_versioned() {
+        let json = serde_json::to_value(state()).unwrap();
+        assert_eq!(json["version"], 1);
+    }
+
+    #[rstest]
+    #[case::unsupported_version("version: 1", "version: 2", "unsupported state format version: 2")]
+    #[case::invalid_speed("speed: 1.5", "speed: .inf", "invalid speed: inf")]
+    fn invalid(#[case] field: &str, #[case] replacement: &str, #[case] message: &str) {
+        let yaml = serde_yaml::to_string(&state()).unwrap();
+        assert!(yaml.contains(field), "{yaml}");
+        let yaml = yaml.replace(field, replacement);
+        let error = serde_yaml::from_str::<State>(&yaml).unwrap_err();
+        assert!(error.to_string().contains(message), "{error}");
+    }
+}
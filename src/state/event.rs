@@ -0,0 +1,32 @@
+/// Something that happened while updating an animation [`State`](crate::State)
+///
+/// See [`State::update_with_events`](crate::State::update_with_events)
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum Event<'a> {
+    /// A frame has been entered
+    ///
+    /// This is reported for every frame, including the ones that were skipped because the elapsed time was
+    /// longer than their duration.
+    #[non_exhaustive]
+    FrameEntered {
+        /// Position of the frame in the animation
+        position: usize,
+        /// Index of the frame in the sprite atlas
+        ///
+        /// It is `None` if the value of the frame has no [sprite index](crate::SpriteIndex::sprite_index).
+        index: Option<usize>,
+    },
+    /// A [marker](crate::Animation::with_marker) has been reached
+    #[non_exhaustive]
+    Marker {
+        /// Name of the marker
+        name: &'a str,
+    },
+    /// A repeating animation went back to the frame it repeats from
+    Looped,
+    /// A ping-pong animation changed direction
+    DirectionChanged,
+    /// An animation that does not repeat forever reached its end
+    Ended,
+}
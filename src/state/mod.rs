@@ -0,0 +1,2340 @@
+use std::{
+    hash::{Hash, Hasher},
+    num::NonZeroU32,
+    ops::Bound,
+    time::Duration,
+};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{animation::Mode, Animation, Frame, FrameMetadata, SpriteIndex, TickRate};
+
+pub use crossfade::{Crossfade, CrossfadeOutcome};
+pub use event::Event;
+pub use outcome::UpdateOutcome;
+pub use queue::AnimationQueue;
+pub use rebind::RebindPolicy;
+
+mod batch;
+mod crossfade;
+#[cfg(feature = "serde")]
+mod dto;
+mod event;
+mod outcome;
+mod queue;
+mod rebind;
+mod switch;
+
+/// Animation state
+///
+/// States are compared and hashed exactly, including the [speed](Self::speed),
+/// so that two states are equal only if they produce the same frames when updated in the same way.
+#[cfg_attr(
+    feature = "serde",
+    doc = "
+
+# Serialization
+
+The serialization format is versioned, so that states stored in save files remain readable by future versions.
+"
+)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(try_from = "dto::StateDto", into = "dto::StateDto")
+)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct State {
+    animation_frame_index: usize,
+    sprite_frame_index: usize,
+    elapsed_in_frame: Duration,
+    /// Total time played since the animation started
+    elapsed: Duration,
+    /// Control `ping_pong` backward frame navigation.
+    going_backward: bool,
+    is_ended: bool,
+    /// Whether the first frame has been entered
+    is_started: bool,
+    /// Playback speed multiplier
+    speed: f64,
+    /// Number of loops (or ping-pong sweeps) completed, for the animations played a finite number of times
+    loops: u32,
+    /// Whether the loop of a `sustain` animation should stop repeating
+    is_released: bool,
+    /// Animation the state was last updated with, if known
+    binding: Option<rebind::Binding>,
+}
+
+impl State {
+    /// Create a new state
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reset animation state
+    ///
+    /// The animation will restart from the first frame, like if the animation was freshly spawned.
+    ///
+    /// The [speed](Self::speed) is preserved.
+    pub fn reset(&mut self) {
+        *self = Self {
+            speed: self.speed,
+            ..Self::default()
+        };
+    }
+
+    /// Returns the playback speed multiplier
+    ///
+    /// See [`set_speed`](Self::set_speed)
+    #[must_use]
+    pub fn speed(&self) -> f64 {
+        self.speed
+    }
+
+    /// Set the playback speed multiplier
+    ///
+    /// The `delta` given to [`update`](Self::update) is multiplied by the absolute value of the speed.
+    /// The default is `1.0`. A speed of `0.0` pauses the animation,
+    /// and a negative speed plays the animation backward.
+    ///
+    /// When playing backward:
+    /// * a repeating animation goes back to its last frame when reaching the frame it repeats from
+    /// * a ping-pong animation goes back and forth, like when playing forward
+    /// * an animation played [`once`](Animation::once) ends on its first frame
+    /// * an animation played a finite number of times goes back through its completed loops,
+    ///   and then ends on its first frame
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use std::time::Duration;
+    /// use benimator::*;
+    ///
+    /// let animation = Animation::from_indices(0..=3, FrameRate::from_fps(10.0));
+    /// let mut state = State::new();
+    ///
+    /// state.set_speed(2.0);
+    /// state.update(&animation, Duration::from_millis(100));
+    /// assert_eq!(state.frame_index(), 2);
+    ///
+    /// state.set_speed(-1.0);
+    /// state.update(&animation, Duration::from_millis(100));
+    /// assert_eq!(state.frame_index(), 1);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the speed is not finite
+    pub fn set_speed(&mut self, speed: f64) {
+        assert!(speed.is_finite(), "Invalid speed: {speed}");
+        self.speed = speed;
+    }
+
+    /// Release the state, so that a [`sustain`](Animation::sustain) animation stops repeating its loop
+    ///
+    /// The current pass of the loop is completed, and then the rest of the animation is played.
+    /// It has no effect on other animations.
+    pub fn release(&mut self) {
+        self.is_released = true;
+    }
+
+    /// Hold the state, so that a [`sustain`](Animation::sustain) animation repeats its loop
+    ///
+    /// This is the default, and may be used to cancel a [`release`](Self::release) before the end of the loop.
+    pub fn hold(&mut self) {
+        self.is_released = false;
+    }
+
+    /// Returns true if the state has been [released](Self::release)
+    #[must_use]
+    pub fn is_released(&self) -> bool {
+        self.is_released
+    }
+
+    /// Returns the current frame index
+    ///
+    /// For an [`AtlasIndex`](crate::AtlasIndex) value, it is the index in its atlas.
+    /// If the value of the current frame has no [sprite index](SpriteIndex::sprite_index),
+    /// it is the index of the last frame entered that has one.
+    /// The value itself is returned by [`update`](Self::update) and [`frame_value`](Self::frame_value),
+    /// and the placement of the sprite by [`frame_metadata`](Self::frame_metadata).
+    #[must_use]
+    pub fn frame_index(&self) -> usize {
+        self.sprite_frame_index
+    }
+
+    /// Returns true if the animation has ended
+    #[must_use]
+    pub fn is_ended(&self) -> bool {
+        self.is_ended
+    }
+
+    /// Returns the time elapsed since the current frame was entered
+    #[must_use]
+    pub fn elapsed_in_frame(&self) -> Duration {
+        self.elapsed_in_frame
+    }
+
+    /// Returns the total time played since the start of the animation
+    ///
+    /// The time is measured in animation time, that is after applying the [speed](Self::speed).
+    /// Playing backward decreases it, down to zero.
+    #[must_use]
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// Returns the progress of the current frame position in the given animation
+    ///
+    /// The progress is between `0.0` (beginning of the first frame) and `1.0` (end of the last frame).
+    /// It ignores the number of loops that have been played, and is the same when a ping-pong animation
+    /// is going forward or backward.
+    ///
+    /// See also [`seek_progress`](Self::seek_progress)
+    #[must_use]
+    pub fn progress<T>(&self, animation: &Animation<T>) -> f64 {
+        let position = self.position(animation);
+        let current = animation.frame_start(position) + self.elapsed_in_frame;
+        (current.as_secs_f64() / animation.duration().as_secs_f64()).min(1.0)
+    }
+
+    /// Returns the time remaining before the end of the animation
+    ///
+    /// The time is measured in animation time, that is without applying the [speed](Self::speed).
+    ///
+    /// Returns `None` if the animation never ends,
+    /// which includes a [`sustain`](Animation::sustain) animation as long as the state is not released.
+    #[must_use]
+    pub fn remaining<T>(&self, animation: &Animation<T>) -> Option<Duration> {
+        if let Mode::Sustain { .. } = animation.mode {
+            if !self.is_released {
+                return None;
+            }
+            let position = self.position(animation);
+            let current = animation.frame_start(position) + self.elapsed_in_frame;
+            return Some(animation.duration().saturating_sub(current));
+        }
+        let end = animation.end_time()?;
+        let current = animation.time_at(
+            self.animation_frame_index,
+            self.elapsed_in_frame,
+            self.going_backward,
+            self.loops,
+        );
+        Some(end.saturating_sub(current))
+    }
+
+    /// Jump to the state the animation would be in, after playing for `elapsed` time from the beginning
+    ///
+    /// No [`Event`] is reported, and the [speed](Self::speed) is ignored.
+    ///
+    /// See also [`Animation::frame_at`] to get the frame at a given time without a state.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use std::time::Duration;
+    /// use benimator::*;
+    ///
+    /// let animation = Animation::from_indices(0..=3, FrameRate::from_fps(10.0));
+    /// let mut state = State::new();
+    ///
+    /// state.seek(&animation, Duration::from_millis(650));
+    /// assert_eq!(state.frame_index(), 2);
+    /// assert_eq!(state.elapsed_in_frame(), Duration::from_millis(50));
+    /// ```
+    pub fn seek<T: SpriteIndex>(&mut self, animation: &Animation<T>, elapsed: Duration) {
+        let sample = animation.frame_at(elapsed);
+        self.place(animation, sample.position, sample.elapsed_in_frame);
+        self.going_backward = sample.going_backward;
+        self.is_ended = sample.is_ended;
+        self.loops = sample.loops;
+        if !sample.is_ended {
+            self.elapsed = elapsed;
+        }
+    }
+
+    /// Jump to the given progress in the animation
+    ///
+    /// The progress must be between `0.0` (beginning of the first frame) and `1.0` (end of the last frame).
+    /// A progress of `1.0` ends an animation played [`once`](Animation::once),
+    /// and goes back to the frame it repeats from for other animations.
+    ///
+    /// No [`Event`] is reported, and the state is set to be moving forward.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the progress is not between `0.0` and `1.0`
+    pub fn seek_progress<T: SpriteIndex>(&mut self, animation: &Animation<T>, progress: f64) {
+        assert!(
+            (0.0..=1.0).contains(&progress),
+            "Invalid progress: {progress}"
+        );
+        self.seek_time(animation, animation.duration().mul_f64(progress));
+    }
+
+    /// Jump to the given time since the beginning of the first frame, ignoring the mode
+    ///
+    /// Reaching the end of the last frame ends an animation played once,
+    /// and goes back to the frame it repeats from for other animations.
+    fn seek_time<T: SpriteIndex>(&mut self, animation: &Animation<T>, target: Duration) {
+        let mut start = Duration::ZERO;
+        for (position, frame) in animation.frames.iter().enumerate() {
+            if target < start + frame.duration {
+                self.place(animation, position, target.saturating_sub(start));
+                return;
+            }
+            start += frame.duration;
+        }
+        let last = animation.frames.len() - 1;
+        self.place(animation, last, animation.frames[last].duration);
+        if self.next_frame(animation, &mut |_| ()) {
+            self.elapsed_in_frame = Duration::ZERO;
+            self.enter_sprite(animation);
+        }
+        self.bind(animation);
+    }
+
+    /// Jump to the beginning of the frame at the given position in the animation
+    ///
+    /// No [`Event`] is reported, and the state is set to be moving forward.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no frame at that position
+    pub fn seek_frame<T: SpriteIndex>(&mut self, animation: &Animation<T>, position: usize) {
+        assert!(
+            position < animation.frames.len(),
+            "Invalid frame position: {position}"
+        );
+        self.place(animation, position, Duration::ZERO);
+    }
+
+    fn place<T: SpriteIndex>(
+        &mut self,
+        animation: &Animation<T>,
+        position: usize,
+        elapsed_in_frame: Duration,
+    ) {
+        let is_released = self.is_released;
+        self.reset();
+        self.is_released = is_released;
+        self.is_started = true;
+        self.animation_frame_index = position;
+        self.elapsed_in_frame = elapsed_in_frame;
+        self.elapsed = animation.frame_start(position) + elapsed_in_frame;
+        self.enter_sprite(animation);
+        self.bind(animation);
+    }
+
+    /// Returns the position of the current frame in the animation
+    ///
+    /// The stored position is only out of range if the state is played with another animation
+    /// that is not told apart from the previous one (see [`rebind`](Self::rebind)), so the division is avoided in the
+    /// common case.
+    fn position<T>(&self, animation: &Animation<T>) -> usize {
+        let frame_count = animation.frames.len();
+        if self.animation_frame_index < frame_count {
+            self.animation_frame_index
+        } else {
+            self.animation_frame_index % frame_count
+        }
+    }
+
+    #[must_use]
+    fn frame<'a, T>(&self, animation: &'a Animation<T>) -> &'a Frame<T> {
+        &animation.frames[self.position(animation)]
+    }
+
+    /// Returns the value of the current frame in the given animation
+    ///
+    /// For an animation created with [`Animation::from_indices`], it is the index in the sprite atlas,
+    /// like [`frame_index`](Self::frame_index).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the animation has no frame
+    #[must_use]
+    pub fn frame_value<'a, T>(&self, animation: &'a Animation<T>) -> &'a T {
+        &self.frame(animation).value
+    }
+
+    /// Returns the [metadata](FrameMetadata) of the current frame in the given animation
+    ///
+    /// # Panics
+    ///
+    /// Panics if the animation has no frame
+    pub fn frame_metadata<'a, T>(&self, animation: &'a Animation<T>) -> &'a FrameMetadata {
+        &self.frame(animation).metadata
+    }
+
+    /// Update the animation state, and returns what changed along with the value of the current frame
+    ///
+    /// The values of the frames must implement [`SpriteIndex`], so that the [frame index](Self::frame_index)
+    /// is kept up to date.
+    ///
+    /// See [`UpdateOutcome`]
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use std::time::Duration;
+    /// use benimator::*;
+    ///
+    /// #[derive(Debug, PartialEq)]
+    /// struct Sprite {
+    ///     atlas: u8,
+    ///     index: usize,
+    /// }
+    ///
+    /// impl SpriteIndex for Sprite {
+    ///     fn sprite_index(&self) -> Option<usize> {
+    ///         Some(self.index)
+    ///     }
+    /// }
+    ///
+    /// let animation = Animation::from_values(
+    ///     [Sprite { atlas: 0, index: 7 }, Sprite { atlas: 1, index: 2 }],
+    ///     FrameRate::from_fps(10.0),
+    /// );
+    /// let mut state = State::new();
+    ///
+    /// let sprite = state.update(&animation, Duration::from_millis(150)).value();
+    /// assert_eq!(sprite, &Sprite { atlas: 1, index: 2 });
+    /// ```
+    pub fn update<'a, T: SpriteIndex>(
+        &mut self,
+        animation: &'a Animation<T>,
+        delta: Duration,
+    ) -> UpdateOutcome<'a, T> {
+        self.update_with(animation, delta, &mut |_| ())
+    }
+
+    /// Update the animation state by the given number of ticks, and returns what changed along with the value of the current frame
+    ///
+    /// The state is updated by the [duration](TickRate::duration) of `ticks` at the given `tick_rate`.
+    /// Frames that are not tick-based are played for their duration, like with [`update`](Self::update).
+    ///
+    /// No floating-point arithmetic is involved as long as the frames are tick-based
+    /// (see [`Frame::from_ticks`] and [`FrameRate::from_ticks`](crate::FrameRate::from_ticks))
+    /// and the [speed](Self::set_speed) is a whole number,
+    /// so that the result is the same on every machine.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use std::num::NonZeroU32;
+    /// use benimator::*;
+    ///
+    /// // Each frame is displayed for 3 ticks of a 60 Hz game loop
+    /// let tick_rate = TickRate::from_hz(NonZeroU32::new(60).unwrap());
+    /// let animation = Animation::from_indices(
+    ///     0..=3,
+    ///     FrameRate::from_ticks(NonZeroU32::new(3).unwrap(), tick_rate),
+    /// );
+    /// let mut state = State::new();
+    ///
+    /// state.update_ticks(&animation, 7, tick_rate);
+    /// assert_eq!(state.frame_index(), 2);
+    /// ```
+    pub fn update_ticks<'a, T: SpriteIndex>(
+        &mut self,
+        animation: &'a Animation<T>,
+        ticks: u32,
+        tick_rate: TickRate,
+    ) -> UpdateOutcome<'a, T> {
+        self.update(animation, tick_rate.duration(ticks))
+    }
+
+    /// Update the animation state by the given number of ticks, and report what happened during the update
+    ///
+    /// See [`update_ticks`](Self::update_ticks) and [`update_with_events`](Self::update_with_events)
+    pub fn update_ticks_with_events<'a, T: SpriteIndex>(
+        &mut self,
+        animation: &'a Animation<T>,
+        ticks: u32,
+        tick_rate: TickRate,
+        events: &mut impl Extend<Event<'a>>,
+    ) -> UpdateOutcome<'a, T> {
+        self.update_with_events(animation, tick_rate.duration(ticks), events)
+    }
+
+    /// Update the animation state, report what happened during the update, and returns what changed
+    ///
+    /// Every [`Event`] is pushed into `events` in the order it happened.
+    /// That includes the frames that are skipped over when `delta` is longer than their duration.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use std::time::Duration;
+    /// use benimator::*;
+    ///
+    /// let animation = Animation::from_indices(0..=3, FrameRate::from_fps(10.0));
+    /// let mut state = State::new();
+    /// let mut events = Vec::new();
+    ///
+    /// state.update_with_events(&animation, Duration::from_millis(250), &mut events);
+    ///
+    /// let entered: Vec<usize> = events
+    ///     .iter()
+    ///     .filter_map(|event| match event {
+    ///         Event::FrameEntered { index, .. } => *index,
+    ///         _ => None,
+    ///     })
+    ///     .collect();
+    /// assert_eq!(entered, vec![0, 1, 2]);
+    /// ```
+    pub fn update_with_events<'a, T: SpriteIndex>(
+        &mut self,
+        animation: &'a Animation<T>,
+        delta: Duration,
+        events: &mut impl Extend<Event<'a>>,
+    ) -> UpdateOutcome<'a, T> {
+        self.update_with(animation, delta, &mut |event| events.extend(Some(event)))
+    }
+
+    /// Update the animation state, and pass every event to `on_event` in the order it happened
+    pub(crate) fn update_with<'a, T: SpriteIndex>(
+        &mut self,
+        animation: &'a Animation<T>,
+        delta: Duration,
+        on_event: &mut impl FnMut(Event<'a>),
+    ) -> UpdateOutcome<'a, T> {
+        self.advance_with_outcome(animation, delta, on_event).0
+    }
+
+    /// Update the animation state, and returns the part of `delta` left over after the end of the animation
+    ///
+    /// The leftover is zero unless the animation ended, or had already ended, during the update.
+    pub(crate) fn update_with_leftover<'a, T: SpriteIndex>(
+        &mut self,
+        animation: &'a Animation<T>,
+        delta: Duration,
+        on_event: &mut impl FnMut(Event<'a>),
+    ) -> Duration {
+        let leftover = self.advance(animation, delta, on_event);
+        if leftover.is_zero() {
+            return leftover;
+        }
+        let unscaled = match whole_speed(self.speed) {
+            Some(speed) => Some(leftover / speed),
+            None => Duration::try_from_secs_f64(leftover.as_secs_f64() / self.speed.abs()).ok(),
+        };
+        unscaled.map_or(delta, |leftover| leftover.min(delta))
+    }
+
+    /// Move the play-head by `delta`, and returns the part of the scaled `delta` left over after the end
+    fn advance<'a, T: SpriteIndex>(
+        &mut self,
+        animation: &'a Animation<T>,
+        delta: Duration,
+        on_event: &mut impl FnMut(Event<'a>),
+    ) -> Duration {
+        debug_assert!(animation.has_frames());
+        if !self.is_bound_to(animation) {
+            self.rebind(animation, RebindPolicy::Clamp);
+        }
+        let leftover = self.move_play_head(animation, delta, on_event);
+        self.bind(animation);
+        leftover
+    }
+
+    /// Move the play-head by `delta`, without checking nor updating the binding to the animation
+    ///
+    /// Returns the part of the scaled `delta` left over after the end
+    fn move_play_head<'a, T: SpriteIndex>(
+        &mut self,
+        animation: &'a Animation<T>,
+        delta: Duration,
+        on_event: &mut impl FnMut(Event<'a>),
+    ) -> Duration {
+        self.enter_sprite(animation);
+        if !self.is_started {
+            self.is_started = true;
+            on_event(self.frame_entered(animation));
+            let elapsed = self.elapsed_in_frame;
+            self.report_markers(
+                animation,
+                (Bound::Included(elapsed), Bound::Included(elapsed)),
+                on_event,
+            );
+        }
+        let delta = self.scale(delta);
+        if self.speed.is_sign_negative() {
+            self.rewind(animation, delta, on_event)
+        } else {
+            self.play(animation, delta, on_event)
+        }
+    }
+
+    /// Remember the animation the state is played with, and where the state is in that animation
+    fn bind<T>(&mut self, animation: &Animation<T>) {
+        self.binding = Some(rebind::Binding::new(animation, self.position(animation)));
+    }
+
+    /// Scale the `delta` by the absolute value of the speed
+    ///
+    /// A whole speed is applied without floating-point arithmetic.
+    fn scale(&self, delta: Duration) -> Duration {
+        match whole_speed(self.speed) {
+            Some(speed) => delta.checked_mul(speed).unwrap_or(Duration::MAX),
+            None => Duration::try_from_secs_f64(delta.as_secs_f64() * self.speed.abs())
+                .unwrap_or(Duration::MAX),
+        }
+    }
+
+    /// Move the play-head forward by `delta`, and returns the part of `delta` left over after the end
+    fn play<'a, T: SpriteIndex>(
+        &mut self,
+        animation: &'a Animation<T>,
+        delta: Duration,
+        on_event: &mut impl FnMut(Event<'a>),
+    ) -> Duration {
+        let mut frame = self.frame(animation);
+        if self.is_ended {
+            if delta.is_zero() || self.elapsed_in_frame >= frame.duration {
+                return delta;
+            }
+            self.is_ended = false;
+        }
+        // Markers of the current frame are reported if their offset is after this bound
+        let mut markers_after = Bound::Excluded(self.elapsed_in_frame);
+        self.elapsed_in_frame += delta;
+        self.elapsed = self.elapsed.saturating_add(delta);
+        while self.elapsed_in_frame >= frame.duration {
+            self.report_markers(
+                animation,
+                (markers_after, Bound::Excluded(frame.duration)),
+                on_event,
+            );
+            if !self.next_frame(animation, on_event) {
+                let leftover = self.elapsed_in_frame.saturating_sub(frame.duration);
+                self.elapsed = self.elapsed.saturating_sub(leftover);
+                self.elapsed_in_frame = frame.duration;
+                return leftover;
+            }
+            self.elapsed_in_frame -= frame.duration;
+            frame = self.frame(animation);
+            self.enter_sprite(animation);
+            on_event(self.frame_entered(animation));
+            markers_after = Bound::Included(Duration::ZERO);
+        }
+        self.report_markers(
+            animation,
+            (markers_after, Bound::Included(self.elapsed_in_frame)),
+            on_event,
+        );
+        Duration::ZERO
+    }
+
+    /// Move the play-head backward by `delta`, and returns the part of `delta` left over after the end
+    fn rewind<'a, T: SpriteIndex>(
+        &mut self,
+        animation: &'a Animation<T>,
+        mut delta: Duration,
+        on_event: &mut impl FnMut(Event<'a>),
+    ) -> Duration {
+        if self.is_ended {
+            if delta.is_zero() || self.elapsed_in_frame.is_zero() {
+                return delta;
+            }
+            self.is_ended = false;
+        }
+        // Markers of the current frame are reported if their offset is before this bound
+        let mut markers_before = Bound::Excluded(self.elapsed_in_frame);
+        while delta > self.elapsed_in_frame {
+            self.report_markers(
+                animation,
+                (Bound::Included(Duration::ZERO), markers_before),
+                on_event,
+            );
+            if !self.previous_frame(animation, on_event) {
+                self.elapsed = self.elapsed.saturating_sub(self.elapsed_in_frame);
+                let leftover = delta.saturating_sub(self.elapsed_in_frame);
+                self.elapsed_in_frame = Duration::ZERO;
+                return leftover;
+            }
+            self.elapsed = self.elapsed.saturating_sub(self.elapsed_in_frame);
+            delta -= self.elapsed_in_frame;
+            let frame = self.frame(animation);
+            self.enter_sprite(animation);
+            self.elapsed_in_frame = frame.duration;
+            on_event(self.frame_entered(animation));
+            markers_before = Bound::Excluded(frame.duration);
+        }
+        self.elapsed_in_frame -= delta;
+        self.elapsed = self.elapsed.saturating_sub(delta);
+        self.report_markers(
+            animation,
+            (Bound::Included(self.elapsed_in_frame), markers_before),
+            on_event,
+        );
+        Duration::ZERO
+    }
+
+    /// Go to the next frame
+    ///
+    /// Returns false if the animation ended instead
+    fn next_frame<'a, T: SpriteIndex>(
+        &mut self,
+        animation: &'a Animation<T>,
+        on_event: &mut impl FnMut(Event<'a>),
+    ) -> bool {
+        let last = animation.frames.len() - 1;
+        let position = self.animation_frame_index.min(last);
+        match animation.clamped_mode() {
+            Mode::RepeatFrom(loop_from) => {
+                if position == last {
+                    self.animation_frame_index = loop_from;
+                    on_event(Event::Looped);
+                } else {
+                    self.animation_frame_index = position + 1;
+                }
+            }
+            Mode::RepeatTimes { from, times } => {
+                if position < last {
+                    self.animation_frame_index = position + 1;
+                } else if self.loops.saturating_add(1) < times.get() {
+                    self.loops += 1;
+                    self.animation_frame_index = from;
+                    on_event(Event::Looped);
+                } else {
+                    return self.end(on_event);
+                }
+            }
+            Mode::Sustain {
+                loop_start,
+                loop_end,
+            } => {
+                if position == loop_end && !self.is_released {
+                    self.loops = self.loops.saturating_add(1);
+                    self.animation_frame_index = loop_start;
+                    on_event(Event::Looped);
+                } else if position == last {
+                    return self.end(on_event);
+                } else {
+                    self.animation_frame_index = position + 1;
+                }
+            }
+            Mode::PingPong => return self.next_ping_pong_frame(last, None, on_event),
+            Mode::PingPongTimes(times) => {
+                return self.next_ping_pong_frame(last, Some(times), on_event)
+            }
+            Mode::Once => {
+                if position == last {
+                    return self.end(on_event);
+                }
+                self.animation_frame_index = position + 1;
+            }
+        }
+        true
+    }
+
+    /// Go to the next frame of a ping-pong animation, ending after the given number of sweeps, if any
+    ///
+    /// The direction changes when leaving the first or last frame,
+    /// so that `going_backward` tells in which direction the current frame was entered.
+    fn next_ping_pong_frame<'a>(
+        &mut self,
+        last: usize,
+        times: Option<NonZeroU32>,
+        on_event: &mut impl FnMut(Event<'a>),
+    ) -> bool {
+        let position = self.animation_frame_index.min(last);
+        let turning = if self.going_backward {
+            position == 0
+        } else {
+            position == last
+        };
+        if !turning {
+            self.animation_frame_index = if self.going_backward {
+                position - 1
+            } else {
+                position + 1
+            };
+            return true;
+        }
+        if times.is_some_and(|times| self.loops.saturating_add(1) >= times.get()) {
+            return self.end(on_event);
+        }
+        self.loops = self.loops.saturating_add(1);
+        if last == 0 {
+            self.animation_frame_index = 0;
+            return true;
+        }
+        self.going_backward = !self.going_backward;
+        self.animation_frame_index = if self.going_backward { last - 1 } else { 1 };
+        on_event(Event::DirectionChanged);
+        true
+    }
+
+    /// Go to the previous frame
+    ///
+    /// Returns false if the animation ended instead
+    fn previous_frame<'a, T: SpriteIndex>(
+        &mut self,
+        animation: &'a Animation<T>,
+        on_event: &mut impl FnMut(Event<'a>),
+    ) -> bool {
+        let last = animation.frames.len() - 1;
+        let position = self.animation_frame_index.min(last);
+        match animation.clamped_mode() {
+            Mode::RepeatFrom(loop_from) => {
+                if position == 0 || position == loop_from {
+                    self.animation_frame_index = last;
+                    on_event(Event::Looped);
+                } else {
+                    self.animation_frame_index = position - 1;
+                }
+            }
+            Mode::RepeatTimes { from, .. } if position == from && self.loops > 0 => {
+                self.loop_back(last, on_event);
+            }
+            Mode::Sustain {
+                loop_start,
+                loop_end,
+            } if position == loop_start && self.loops > 0 => {
+                self.loop_back(loop_end, on_event);
+            }
+            Mode::PingPong => return self.previous_ping_pong_frame(last, false, on_event),
+            Mode::PingPongTimes(_) => return self.previous_ping_pong_frame(last, true, on_event),
+            Mode::Once | Mode::RepeatTimes { .. } | Mode::Sustain { .. } => {
+                if position == 0 {
+                    return self.end(on_event);
+                }
+                self.animation_frame_index = position - 1;
+            }
+        }
+        true
+    }
+
+    /// Go to the previous frame of a ping-pong animation
+    ///
+    /// This undoes [`next_ping_pong_frame`](Self::next_ping_pong_frame).
+    /// An infinite animation keeps going back and forth before its beginning,
+    /// while a `finite` one ends on its first frame.
+    fn previous_ping_pong_frame<'a>(
+        &mut self,
+        last: usize,
+        finite: bool,
+        on_event: &mut impl FnMut(Event<'a>),
+    ) -> bool {
+        let position = self.animation_frame_index.min(last);
+        let at_start = self.loops == 0 && !self.going_backward;
+        if last == 0 {
+            if finite && at_start {
+                return self.end(on_event);
+            }
+            self.loops = self.loops.saturating_sub(1);
+            return true;
+        }
+        if self.going_backward && position + 1 == last {
+            self.going_backward = false;
+            self.loops = self.loops.saturating_sub(1);
+            self.animation_frame_index = last;
+            on_event(Event::DirectionChanged);
+        } else if self.going_backward {
+            self.animation_frame_index = position + 1;
+        } else if position == 0 {
+            if finite {
+                return self.end(on_event);
+            }
+            self.going_backward = last > 1;
+            self.animation_frame_index = 1;
+            on_event(Event::DirectionChanged);
+        } else if position == 1 && !(finite && at_start) {
+            self.going_backward = true;
+            self.loops = self.loops.saturating_sub(1);
+            self.animation_frame_index = 0;
+            on_event(Event::DirectionChanged);
+        } else {
+            self.animation_frame_index = position - 1;
+        }
+        true
+    }
+
+    /// Go back to the end of the loop, undoing the last loop completed
+    fn loop_back<'a>(&mut self, loop_end: usize, on_event: &mut impl FnMut(Event<'a>)) {
+        self.loops -= 1;
+        self.animation_frame_index = loop_end;
+        on_event(Event::Looped);
+    }
+
+    fn end<'a>(&mut self, on_event: &mut impl FnMut(Event<'a>)) -> bool {
+        self.is_ended = true;
+        on_event(Event::Ended);
+        false
+    }
+
+    fn report_markers<'a, T>(
+        &self,
+        animation: &'a Animation<T>,
+        offsets: (Bound<Duration>, Bound<Duration>),
+        on_event: &mut impl FnMut(Event<'a>),
+    ) {
+        let position = self.position(animation);
+        for name in animation.markers(position, offsets) {
+            on_event(Event::Marker { name });
+        }
+    }
+
+    /// Set the sprite index to the one of the current frame, if its value has one
+    fn enter_sprite<T: SpriteIndex>(&mut self, animation: &Animation<T>) {
+        if let Some(index) = animation.sprite_index(self.position(animation)) {
+            self.sprite_frame_index = index;
+        }
+    }
+
+    fn frame_entered<T: SpriteIndex>(&self, animation: &Animation<T>) -> Event<'static> {
+        Event::FrameEntered {
+            position: self.animation_frame_index,
+            index: animation.sprite_index(self.position(animation)),
+        }
+    }
+}
+
+impl State {
+    /// Returns the fields compared and hashed, with the speed as its bit pattern
+    #[allow(clippy::type_complexity)]
+    fn key(
+        &self,
+    ) -> (
+        usize,
+        usize,
+        Duration,
+        Duration,
+        bool,
+        bool,
+        bool,
+        u64,
+        u32,
+        bool,
+    ) {
+        (
+            self.animation_frame_index,
+            self.sprite_frame_index,
+            self.elapsed_in_frame,
+            self.elapsed,
+            self.going_backward,
+            self.is_ended,
+            self.is_started,
+            self.speed.to_bits(),
+            self.loops,
+            self.is_released,
+        )
+    }
+}
+
+impl PartialEq for State {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for State {}
+
+impl Hash for State {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.key().hash(state);
+    }
+}
+
+impl Default for State {
+    fn default() -> Self {
+        Self {
+            animation_frame_index: 0,
+            sprite_frame_index: 0,
+            elapsed_in_frame: Duration::ZERO,
+            elapsed: Duration::ZERO,
+            going_backward: false,
+            is_ended: false,
+            is_started: false,
+            speed: 1.0,
+            loops: 0,
+            is_released: false,
+            binding: None,
+        }
+    }
+}
+
+/// Returns the absolute value of the speed, if it is a whole number greater than zero
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn whole_speed(speed: f64) -> Option<u32> {
+    let speed = speed.abs();
+    (speed >= 1.0 && speed <= f64::from(u32::MAX) && speed.fract() == 0.0).then_some(speed as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FrameRate;
+
+    #[fixture]
+    fn frame_duration() -> Duration {
+        Duration::from_secs(1)
+    }
+
+    #[fixture]
+    fn frame_rate(frame_duration: Duration) -> FrameRate {
+        FrameRate::from_frame_duration(frame_duration)
+    }
+
+    #[fixture]
+    fn smaller_duration(frame_duration: Duration) -> Duration {
+        frame_duration
+            .checked_sub(Duration::from_millis(1))
+            .unwrap()
+    }
+
+    #[rstest]
+    fn sprite_index(frame_rate: FrameRate) {
+        let animation = Animation::from_indices(3..=5, frame_rate);
+        let mut state = State::default();
+        state.update(&animation, Duration::ZERO);
+        assert_eq!(state.frame_index(), 3);
+    }
+
+    mod eq {
+        use std::collections::hash_map::DefaultHasher;
+
+        use super::*;
+
+        fn hash(state: &State) -> u64 {
+            let mut hasher = DefaultHasher::new();
+            state.hash(&mut hasher);
+            hasher.finish()
+        }
+
+        #[rstest]
+        fn same_updates_give_equal_states(frame_rate: FrameRate) {
+            let animation = Animation::from_indices(0..=3, frame_rate).ping_pong();
+            let mut a = State::new();
+            let mut b = State::new();
+            for state in [&mut a, &mut b] {
+                state.set_speed(0.5);
+                state.update(&animation, Duration::from_millis(4500));
+            }
+            assert_eq!(a, b);
+            assert_eq!(hash(&a), hash(&b));
+        }
+
+        #[test]
+        fn different_speeds_give_different_states() {
+            let mut state = State::new();
+            state.set_speed(0.5);
+            assert_ne!(state, State::new());
+            assert_ne!(hash(&state), hash(&State::new()));
+        }
+    }
+
+    mod reset {
+        use super::*;
+
+        #[fixture]
+        fn state() -> State {
+            State {
+                animation_frame_index: 1,
+                elapsed_in_frame: Duration::from_secs(1),
+                ..Default::default()
+            }
+        }
+
+        #[rstest]
+        fn resets_current_frame(mut state: State) {
+            state.reset();
+            assert_eq!(state.animation_frame_index, 0);
+        }
+
+        #[rstest]
+        fn resets_elapsed_time(mut state: State) {
+            state.reset();
+            assert_eq!(state.elapsed_in_frame, Duration::ZERO);
+        }
+    }
+
+    mod on_first_frame {
+        use super::*;
+
+        #[fixture]
+        fn animation(frame_rate: FrameRate) -> Animation {
+            Animation::from_indices(0..=2, frame_rate)
+        }
+
+        #[fixture]
+        fn state() -> State {
+            State::default()
+        }
+
+        #[rstest]
+        fn nothing_happens_if_not_enough_time_has_elapsed_and_index_is_already_set(
+            mut state: State,
+            animation: Animation,
+            smaller_duration: Duration,
+        ) {
+            state.update(&animation, smaller_duration);
+            assert_eq!(state.frame_index(), 0);
+        }
+
+        #[rstest]
+        fn updates_index_if_less_than_expected_index(
+            mut state: State,
+            frame_rate: FrameRate,
+            smaller_duration: Duration,
+        ) {
+            let animation = Animation::from_indices(1..=3, frame_rate);
+            state.update(&animation, smaller_duration);
+            assert_eq!(state.frame_index(), 1);
+        }
+
+        #[rstest]
+        fn updates_index_if_greater_than_expected_index(
+            mut state: State,
+            frame_rate: FrameRate,
+            smaller_duration: Duration,
+        ) {
+            let animation = Animation::from_indices(1..=3, frame_rate);
+            state.update(&animation, smaller_duration);
+            assert_eq!(state.frame_index(), 1);
+        }
+
+        #[rstest]
+        fn updates_index_if_enough_time_has_elapsed(
+            mut state: State,
+            animation: Animation,
+            frame_duration: Duration,
+        ) {
+            state.update(&animation, frame_duration);
+            assert_eq!(state.frame_index(), 1);
+        }
+
+        #[rstest]
+        fn updates_index_if_enough_time_has_elapsed_after_multiple_updates(
+            mut state: State,
+            animation: Animation,
+            smaller_duration: Duration,
+        ) {
+            state.update(&animation, smaller_duration);
+            state.update(&animation, smaller_duration);
+            assert_eq!(state.frame_index(), 1);
+        }
+
+        #[rstest]
+        fn elapsed_duration_is_reset(
+            mut state: State,
+            animation: Animation,
+            frame_duration: Duration,
+            smaller_duration: Duration,
+        ) {
+            state.update(&animation, smaller_duration);
+            state.update(&animation, smaller_duration);
+            assert_eq!(
+                state.elapsed_in_frame,
+                (smaller_duration + smaller_duration)
+                    .checked_sub(frame_duration)
+                    .unwrap()
+            );
+        }
+
+        #[rstest]
+        fn is_not_ended(mut state: State, animation: Animation, frame_duration: Duration) {
+            state.update(&animation, frame_duration);
+            assert!(!state.is_ended());
+        }
+
+        #[rstest]
+        fn skips_frame_if_too_much_time_elapsed(
+            mut state: State,
+            animation: Animation,
+            frame_duration: Duration,
+        ) {
+            state.update(&animation, frame_duration * 2);
+            assert_eq!(state.frame_index(), 2);
+        }
+    }
+
+    mod repeat_from {
+        use crate::Frame;
+
+        use super::*;
+
+        mod on_last_frame {
+            use super::*;
+
+            #[fixture]
+            fn animation(frame_duration: Duration) -> Animation {
+                Animation::from_frames(vec![
+                    Frame::new(0, frame_duration),
+                    Frame::new(1, frame_duration),
+                    Frame::new(2, frame_duration),
+                    Frame::new(3, frame_duration),
+                    Frame::new(4, frame_duration),
+                ])
+                .repeat_from(2)
+            }
+
+            #[fixture]
+            fn state() -> State {
+                State {
+                    animation_frame_index: 4,
+                    elapsed_in_frame: Duration::from_nanos(0),
+                    ..Default::default()
+                }
+            }
+
+            #[rstest]
+            fn returns_to_loop_frame(
+                mut state: State,
+                animation: Animation,
+                frame_duration: Duration,
+            ) {
+                state.update(&animation, frame_duration);
+                assert_eq!(state.frame_index(), 2);
+            }
+
+            #[rstest]
+            fn is_not_ended(mut state: State, animation: Animation, frame_duration: Duration) {
+                state.update(&animation, frame_duration);
+                assert!(!state.is_ended());
+            }
+        }
+
+        mod after_last_frame {
+            use super::*;
+
+            #[fixture]
+            fn animation(frame_duration: Duration) -> Animation {
+                Animation::from_frames(vec![
+                    Frame::new(0, frame_duration),
+                    Frame::new(1, frame_duration),
+                    Frame::new(2, frame_duration),
+                    Frame::new(3, frame_duration),
+                ])
+                .repeat_from(2)
+            }
+
+            #[fixture]
+            fn state() -> State {
+                State {
+                    animation_frame_index: 4,
+                    elapsed_in_frame: Duration::from_nanos(0),
+                    ..Default::default()
+                }
+            }
+
+            #[rstest]
+            fn returns_to_first_frame(
+                mut state: State,
+                animation: Animation,
+                frame_duration: Duration,
+            ) {
+                state.update(&animation, frame_duration);
+                assert_eq!(state.frame_index(), 2);
+            }
+
+            #[rstest]
+            fn is_not_ended(mut state: State, animation: Animation, frame_duration: Duration) {
+                state.update(&animation, frame_duration);
+                assert!(!state.is_ended());
+            }
+        }
+    }
+
+    mod ping_pong {
+        use super::*;
+
+        mod on_last_frame {
+            use super::*;
+
+            #[fixture]
+            fn animation(frame_rate: FrameRate) -> Animation {
+                Animation::from_indices(0..=1, frame_rate).ping_pong()
+            }
+
+            #[fixture]
+            fn state() -> State {
+                State {
+                    animation_frame_index: 1,
+                    elapsed_in_frame: Duration::from_nanos(500),
+                    ..Default::default()
+                }
+            }
+
+            #[rstest]
+            fn returns_to_previous_frame(
+                mut state: State,
+                animation: Animation,
+                frame_duration: Duration,
+            ) {
+                state.update(&animation, frame_duration);
+                assert_eq!(state.frame_index(), 0);
+            }
+
+            #[rstest]
+            fn changes_state_to_backward(
+                mut state: State,
+                animation: Animation,
+                frame_duration: Duration,
+            ) {
+                state.update(&animation, frame_duration);
+                assert!(state.going_backward);
+            }
+        }
+
+        mod going_backward {
+            use super::*;
+
+            #[fixture]
+            fn animation(frame_rate: FrameRate) -> Animation {
+                Animation::from_indices(0..=2, frame_rate).ping_pong()
+            }
+
+            #[fixture]
+            fn state() -> State {
+                State {
+                    animation_frame_index: 1,
+                    elapsed_in_frame: Duration::from_nanos(500),
+                    going_backward: true,
+                    ..Default::default()
+                }
+            }
+
+            #[rstest]
+            fn continues_to_previous_frame(
+                mut state: State,
+                animation: Animation,
+                frame_duration: Duration,
+            ) {
+                state.update(&animation, frame_duration);
+                assert_eq!(state.frame_index(), 0);
+            }
+        }
+    }
+
+    #[rstest]
+    fn single_frame_ping_pong(frame_rate: FrameRate, frame_duration: Duration) {
+        let animation = Animation::from_indices(3..=3, frame_rate).ping_pong();
+        let mut state = State::new();
+        state.update(&animation, frame_duration * 3);
+        assert_eq!(state.frame_index(), 3);
+    }
+
+    mod run_once {
+        use super::*;
+
+        #[fixture]
+        fn animation(frame_rate: FrameRate) -> Animation {
+            Animation::from_indices(0..=1, frame_rate).once()
+        }
+
+        mod on_first_frame {
+            use super::*;
+
+            #[fixture]
+            fn state() -> State {
+                State {
+                    animation_frame_index: 0,
+                    elapsed_in_frame: Duration::from_nanos(500),
+                    ..Default::default()
+                }
+            }
+
+            #[rstest]
+            fn final_index_set_if_frames_skipped_past_end(
+                mut state: State,
+                animation: Animation,
+                frame_duration: Duration,
+            ) {
+                state.update(&animation, frame_duration * 4);
+                assert_eq!(state.frame_index(), 1);
+            }
+        }
+
+        mod on_last_frame {
+            use super::*;
+
+            #[fixture]
+            fn state() -> State {
+                State {
+                    animation_frame_index: 1,
+                    elapsed_in_frame: Duration::from_nanos(500),
+                    ..Default::default()
+                }
+            }
+
+            #[rstest]
+            fn does_nothing(mut state: State, animation: Animation, frame_duration: Duration) {
+                state.update(&animation, frame_duration);
+                assert_eq!(state.frame_index(), 1);
+            }
+
+            #[rstest]
+            fn is_ended(mut state: State, animation: Animation, frame_duration: Duration) {
+                state.update(&animation, frame_duration);
+                assert!(state.is_ended());
+            }
+        }
+    }
+
+    mod finite {
+        use super::*;
+
+        fn times(n: u32) -> NonZeroU32 {
+            NonZeroU32::new(n).unwrap()
+        }
+
+        fn play(animation: &Animation, state: &mut State, step: Duration, n: usize) -> Vec<usize> {
+            (0..n)
+                .map(|_| {
+                    state.update(animation, step);
+                    state.frame_index()
+                })
+                .collect()
+        }
+
+        #[rstest]
+        fn repeat_times(frame_rate: FrameRate, frame_duration: Duration) {
+            let animation = Animation::from_indices(0..=2, frame_rate).repeat_times(times(2));
+            let mut state = State::new();
+            assert_eq!(
+                play(&animation, &mut state, frame_duration, 6),
+                vec![1, 2, 0, 1, 2, 2]
+            );
+            assert!(state.is_ended());
+        }
+
+        #[rstest]
+        fn repeat_from_times(frame_rate: FrameRate, frame_duration: Duration) {
+            let animation =
+                Animation::from_indices(0..=2, frame_rate).repeat_from_times(1, times(3));
+            let mut state = State::new();
+            assert_eq!(
+                play(&animation, &mut state, frame_duration, 7),
+                vec![1, 2, 1, 2, 1, 2, 2]
+            );
+            assert!(state.is_ended());
+        }
+
+        #[rstest]
+        fn repeat_once_is_same_as_once(frame_rate: FrameRate, frame_duration: Duration) {
+            let animation = Animation::from_indices(0..=2, frame_rate).repeat_times(times(1));
+            let mut state = State::new();
+            state.update(&animation, frame_duration * 3);
+            assert_eq!(state.frame_index(), 2);
+            assert!(state.is_ended());
+        }
+
+        #[rstest]
+        #[case(1, vec![1, 2, 2, 2], 2)]
+        #[case(2, vec![1, 2, 1, 0, 0], 0)]
+        #[case(3, vec![1, 2, 1, 0, 1, 2, 2], 2)]
+        fn ping_pong_times(
+            frame_rate: FrameRate,
+            frame_duration: Duration,
+            #[case] sweeps: u32,
+            #[case] expected: Vec<usize>,
+            #[case] last_index: usize,
+        ) {
+            let animation =
+                Animation::from_indices(0..=2, frame_rate).ping_pong_times(times(sweeps));
+            let mut state = State::new();
+            assert_eq!(
+                play(&animation, &mut state, frame_duration, expected.len()),
+                expected
+            );
+            assert!(state.is_ended());
+            assert_eq!(state.frame_index(), last_index);
+        }
+
+        #[rstest]
+        fn reports_end(frame_rate: FrameRate, frame_duration: Duration) {
+            let animation = Animation::from_indices(0..=1, frame_rate).repeat_times(times(2));
+            let mut state = State::new();
+            let mut events = Vec::new();
+            state.update_with_events(&animation, frame_duration * 10, &mut events);
+            assert_eq!(
+                events
+                    .iter()
+                    .filter(|e| matches!(e, Event::Looped | Event::Ended))
+                    .collect::<Vec<_>>(),
+                vec![&Event::Looped, &Event::Ended]
+            );
+        }
+
+        #[rstest]
+        fn remaining(frame_rate: FrameRate, frame_duration: Duration) {
+            let animation = Animation::from_indices(0..=2, frame_rate).ping_pong_times(times(3));
+            let mut state = State::new();
+            assert_eq!(state.remaining(&animation), Some(frame_duration * 7));
+            state.update(&animation, frame_duration * 4 + frame_duration / 2);
+            assert_eq!(
+                state.remaining(&animation),
+                Some(frame_duration * 2 + frame_duration / 2)
+            );
+        }
+
+        mod sustain {
+            use super::*;
+
+            #[fixture]
+            fn animation(frame_rate: FrameRate) -> Animation {
+                Animation::from_indices(0..=4, frame_rate).sustain(1..=2)
+            }
+
+            #[rstest]
+            fn loops_while_held(animation: Animation, frame_duration: Duration) {
+                let mut state = State::new();
+                assert_eq!(
+                    play(&animation, &mut state, frame_duration, 6),
+                    vec![1, 2, 1, 2, 1, 2]
+                );
+                assert!(!state.is_ended());
+                assert_eq!(state.remaining(&animation), None);
+            }
+
+            #[rstest]
+            fn plays_outro_when_released(animation: Animation, frame_duration: Duration) {
+                let mut state = State::new();
+                state.update(&animation, frame_duration * 3);
+                state.release();
+                assert_eq!(state.remaining(&animation), Some(frame_duration * 4));
+                assert_eq!(
+                    play(&animation, &mut state, frame_duration, 4),
+                    vec![2, 3, 4, 4]
+                );
+                assert!(state.is_ended());
+            }
+
+            #[rstest]
+            fn hold_cancels_release(animation: Animation, frame_duration: Duration) {
+                let mut state = State::new();
+                state.update(&animation, frame_duration);
+                state.release();
+                assert!(state.is_released());
+                state.hold();
+                assert_eq!(
+                    play(&animation, &mut state, frame_duration, 3),
+                    vec![2, 1, 2]
+                );
+            }
+
+            #[rstest]
+            fn released_before_loop(animation: Animation, frame_duration: Duration) {
+                let mut state = State::new();
+                state.release();
+                assert_eq!(
+                    play(&animation, &mut state, frame_duration, 4),
+                    vec![1, 2, 3, 4]
+                );
+            }
+
+            #[test]
+            fn reset_holds_again() {
+                let mut state = State::new();
+                state.release();
+                state.reset();
+                assert!(!state.is_released());
+            }
+
+            #[rstest]
+            fn seek_preserves_release(animation: Animation) {
+                let mut state = State::new();
+                state.release();
+                state.seek(&animation, Duration::ZERO);
+                assert!(state.is_released());
+            }
+        }
+    }
+
+    mod events {
+        use super::*;
+
+        fn update<'a>(
+            state: &mut State,
+            animation: &'a Animation,
+            delta: Duration,
+        ) -> Vec<Event<'a>> {
+            let mut events = Vec::new();
+            state.update_with_events(animation, delta, &mut events);
+            events
+        }
+
+        fn entered(position: usize, index: usize) -> Event<'static> {
+            Event::FrameEntered {
+                position,
+                index: Some(index),
+            }
+        }
+
+        #[rstest]
+        fn reports_first_frame_on_first_update(frame_rate: FrameRate) {
+            let animation = Animation::from_indices(3..=5, frame_rate);
+            let mut state = State::new();
+            assert_eq!(
+                update(&mut state, &animation, Duration::ZERO),
+                vec![entered(0, 3)]
+            );
+            assert_eq!(update(&mut state, &animation, Duration::ZERO), vec![]);
+        }
+
+        #[rstest]
+        fn reports_nothing_if_frame_does_not_change(
+            frame_rate: FrameRate,
+            smaller_duration: Duration,
+        ) {
+            let animation = Animation::from_indices(0..=2, frame_rate);
+            let mut state = State::new();
+            state.update(&animation, Duration::ZERO);
+            assert_eq!(update(&mut state, &animation, smaller_duration), vec![]);
+        }
+
+        #[rstest]
+        fn reports_skipped_frames(frame_rate: FrameRate, frame_duration: Duration) {
+            let animation = Animation::from_indices(3..=6, frame_rate);
+            let mut state = State::new();
+            state.update(&animation, Duration::ZERO);
+            assert_eq!(
+                update(&mut state, &animation, frame_duration * 3),
+                vec![entered(1, 4), entered(2, 5), entered(3, 6)]
+            );
+        }
+
+        #[rstest]
+        fn reports_loops(frame_rate: FrameRate, frame_duration: Duration) {
+            let animation = Animation::from_indices(0..=2, frame_rate).repeat_from(1);
+            let mut state = State::new();
+            state.update(&animation, Duration::ZERO);
+            assert_eq!(
+                update(&mut state, &animation, frame_duration * 4),
+                vec![
+                    entered(1, 1),
+                    entered(2, 2),
+                    Event::Looped,
+                    entered(1, 1),
+                    entered(2, 2),
+                ]
+            );
+        }
+
+        #[rstest]
+        fn reports_loop_of_single_frame_animation(frame_rate: FrameRate, frame_duration: Duration) {
+            let animation = Animation::from_indices(0..=0, frame_rate);
+            let mut state = State::new();
+            state.update(&animation, Duration::ZERO);
+            assert_eq!(
+                update(&mut state, &animation, frame_duration),
+                vec![Event::Looped, entered(0, 0)]
+            );
+        }
+
+        #[rstest]
+        fn reports_direction_changes(frame_rate: FrameRate, frame_duration: Duration) {
+            let animation = Animation::from_indices(0..=1, frame_rate).ping_pong();
+            let mut state = State::new();
+            state.update(&animation, Duration::ZERO);
+            assert_eq!(
+                update(&mut state, &animation, frame_duration * 3),
+                vec![
+                    entered(1, 1),
+                    Event::DirectionChanged,
+                    entered(0, 0),
+                    Event::DirectionChanged,
+                    entered(1, 1),
+                ]
+            );
+        }
+
+        #[rstest]
+        fn reports_end_once(frame_rate: FrameRate, frame_duration: Duration) {
+            let animation = Animation::from_indices(0..=1, frame_rate).once();
+            let mut state = State::new();
+            state.update(&animation, Duration::ZERO);
+            assert_eq!(
+                update(&mut state, &animation, frame_duration * 5),
+                vec![entered(1, 1), Event::Ended]
+            );
+            assert_eq!(update(&mut state, &animation, frame_duration * 5), vec![]);
+        }
+
+        mod markers {
+            use super::*;
+
+            fn markers(events: &[Event<'_>]) -> Vec<String> {
+                events
+                    .iter()
+                    .filter_map(|event| match event {
+                        Event::Marker { name } => Some((*name).to_owned()),
+                        _ => None,
+                    })
+                    .collect()
+            }
+
+            #[rstest]
+            fn reports_marker_of_first_frame_on_first_update(frame_rate: FrameRate) {
+                let animation = Animation::from_indices(0..=2, frame_rate).with_marker(0, "a");
+                let mut state = State::new();
+                let events = update(&mut state, &animation, Duration::ZERO);
+                assert_eq!(events, vec![entered(0, 0), Event::Marker { name: "a" }]);
+                assert_eq!(update(&mut state, &animation, Duration::ZERO), vec![]);
+            }
+
+            #[rstest]
+            fn reports_marker_after_frame_entered(frame_rate: FrameRate, frame_duration: Duration) {
+                let animation = Animation::from_indices(0..=2, frame_rate).with_marker(1, "a");
+                let mut state = State::new();
+                state.update(&animation, Duration::ZERO);
+                assert_eq!(
+                    update(&mut state, &animation, frame_duration),
+                    vec![entered(1, 1), Event::Marker { name: "a" }]
+                );
+            }
+
+            #[rstest]
+            fn reports_markers_of_skipped_frames(frame_rate: FrameRate, frame_duration: Duration) {
+                let animation = Animation::from_indices(0..=3, frame_rate)
+                    .with_marker(2, "b")
+                    .with_marker(1, "a")
+                    .with_marker(3, "c");
+                let mut state = State::new();
+                state.update(&animation, Duration::ZERO);
+                let events = update(&mut state, &animation, frame_duration * 2);
+                assert_eq!(markers(&events), vec!["a", "b"]);
+            }
+
+            #[rstest]
+            fn reports_marker_when_offset_is_reached(frame_rate: FrameRate) {
+                let animation = Animation::from_indices(0..=2, frame_rate).with_marker_at(
+                    0,
+                    Duration::from_millis(500),
+                    "a",
+                );
+                let mut state = State::new();
+                let events = update(&mut state, &animation, Duration::from_millis(499));
+                assert_eq!(markers(&events), Vec::<String>::new());
+                let events = update(&mut state, &animation, Duration::from_millis(1));
+                assert_eq!(markers(&events), vec!["a"]);
+                let events = update(&mut state, &animation, Duration::from_millis(1));
+                assert_eq!(markers(&events), Vec::<String>::new());
+            }
+
+            #[rstest]
+            fn reports_markers_in_order_of_offset(frame_rate: FrameRate) {
+                let animation = Animation::from_indices(0..=2, frame_rate)
+                    .with_marker_at(0, Duration::from_millis(600), "b")
+                    .with_marker_at(0, Duration::from_millis(300), "a");
+                let mut state = State::new();
+                let events = update(&mut state, &animation, Duration::from_millis(700));
+                assert_eq!(markers(&events), vec!["a", "b"]);
+            }
+
+            #[rstest]
+            fn reports_markers_every_loop(frame_rate: FrameRate, frame_duration: Duration) {
+                let animation = Animation::from_indices(0..=1, frame_rate).with_marker(0, "a");
+                let mut state = State::new();
+                let events = update(&mut state, &animation, frame_duration * 4);
+                assert_eq!(markers(&events), vec!["a", "a", "a"]);
+            }
+
+            #[rstest]
+            fn does_not_report_markers_after_end(frame_rate: FrameRate, frame_duration: Duration) {
+                let animation = Animation::from_indices(0..=1, frame_rate)
+                    .once()
+                    .with_marker_at(1, Duration::from_millis(500), "a");
+                let mut state = State::new();
+                let events = update(&mut state, &animation, frame_duration * 3);
+                assert_eq!(markers(&events), vec!["a"]);
+                let events = update(&mut state, &animation, frame_duration * 3);
+                assert_eq!(markers(&events), Vec::<String>::new());
+            }
+        }
+
+        #[rstest]
+        fn reset_reports_first_frame_again(frame_rate: FrameRate, frame_duration: Duration) {
+            let animation = Animation::from_indices(0..=2, frame_rate);
+            let mut state = State::new();
+            state.update(&animation, frame_duration);
+            state.reset();
+            assert_eq!(
+                update(&mut state, &animation, Duration::ZERO),
+                vec![entered(0, 0)]
+            );
+        }
+    }
+
+    mod ticks {
+        use super::*;
+
+        #[fixture]
+        fn tick_rate() -> TickRate {
+            TickRate::from_hz(NonZeroU32::new(60).unwrap())
+        }
+
+        #[fixture]
+        fn animation(tick_rate: TickRate) -> Animation {
+            Animation::from_frames([
+                Frame::from_ticks(0, NonZeroU32::new(2).unwrap(), tick_rate),
+                Frame::from_ticks(1, NonZeroU32::new(3).unwrap(), tick_rate),
+                Frame::from_ticks(2, NonZeroU32::new(1).unwrap(), tick_rate),
+            ])
+            .ping_pong()
+        }
+
+        #[rstest]
+        fn changes_frame_after_its_ticks(animation: Animation, tick_rate: TickRate) {
+            let mut state = State::new();
+            let mut indices = Vec::new();
+            for _ in 0..12 {
+                state.update_ticks(&animation, 1, tick_rate);
+                indices.push(state.frame_index());
+            }
+            assert_eq!(indices, vec![0, 1, 1, 1, 2, 1, 1, 1, 0, 0, 1, 1]);
+        }
+
+        #[rstest]
+        fn same_result_whatever_the_steps(
+            animation: Animation,
+            tick_rate: TickRate,
+            #[values(1.0, 2.0, -3.0)] speed: f64,
+        ) {
+            let mut by_one = State::new();
+            by_one.set_speed(speed);
+            for _ in 0..1000 {
+                by_one.update_ticks(&animation, 1, tick_rate);
+            }
+            let mut at_once = State::new();
+            at_once.set_speed(speed);
+            at_once.update_ticks(&animation, 1000, tick_rate);
+            assert_eq!(by_one, at_once);
+        }
+
+        #[rstest]
+        fn elapsed_is_the_duration_of_the_ticks(animation: Animation, tick_rate: TickRate) {
+            let animation = animation.once();
+            let mut state = State::new();
+            state.update_ticks(&animation, 4, tick_rate);
+            assert_eq!(state.elapsed(), tick_rate.tick_duration() * 4);
+            assert_eq!(
+                state.remaining(&animation),
+                Some(tick_rate.tick_duration() * 2)
+            );
+        }
+
+        #[rstest]
+        fn mixes_with_other_frame_rates(tick_rate: TickRate) {
+            let animation = Animation::from_indices(0..=3, FrameRate::from_fps(20.0));
+            let mut state = State::new();
+            state.update_ticks(&animation, 7, tick_rate);
+            assert_eq!(state.frame_index(), 2);
+        }
+
+        #[rstest]
+        fn reports_events(animation: Animation, tick_rate: TickRate) {
+            let mut state = State::new();
+            let mut events = Vec::new();
+            state.update_ticks_with_events(&animation, 2, tick_rate, &mut events);
+            assert_eq!(
+                events,
+                vec![
+                    Event::FrameEntered {
+                        position: 0,
+                        index: Some(0)
+                    },
+                    Event::FrameEntered {
+                        position: 1,
+                        index: Some(1)
+                    }
+                ]
+            );
+        }
+    }
+
+    mod values {
+        use super::*;
+
+        /// Sprite of an atlas, or nothing displayed
+        #[derive(Debug, PartialEq)]
+        struct Sprite(u8, Option<usize>);
+
+        impl SpriteIndex for Sprite {
+            fn sprite_index(&self) -> Option<usize> {
+                self.1
+            }
+        }
+
+        #[fixture]
+        fn animation(frame_rate: FrameRate) -> Animation<Sprite> {
+            Animation::from_values(
+                [Sprite(0, Some(5)), Sprite(1, Some(6)), Sprite(0, None)],
+                frame_rate,
+            )
+        }
+
+        #[rstest]
+        fn update_returns_the_current_value(
+            animation: Animation<Sprite>,
+            frame_duration: Duration,
+        ) {
+            let mut state = State::new();
+            assert_eq!(
+                state.update(&animation, Duration::ZERO).value(),
+                &Sprite(0, Some(5))
+            );
+            assert_eq!(
+                state.update(&animation, frame_duration).value(),
+                &Sprite(1, Some(6))
+            );
+            let tick_rate = TickRate::from_hz(NonZeroU32::new(1000).unwrap());
+            assert_eq!(
+                state.update_ticks(&animation, 1000, tick_rate).value(),
+                &Sprite(0, None)
+            );
+            assert_eq!(state.frame_value(&animation), &Sprite(0, None));
+        }
+
+        #[rstest]
+        fn frame_index_is_the_index_of_the_sprite(
+            animation: Animation<Sprite>,
+            frame_duration: Duration,
+        ) {
+            let mut state = State::new();
+            state.update(&animation, frame_duration);
+            assert_eq!(state.frame_index(), 6);
+            assert_eq!(animation.frame_at(frame_duration).index(), Some(6));
+        }
+
+        #[rstest]
+        fn frame_without_sprite_index_keeps_the_previous_index(
+            animation: Animation<Sprite>,
+            frame_duration: Duration,
+        ) {
+            let mut state = State::new();
+            let mut events = Vec::new();
+            state.update_with_events(&animation, frame_duration * 2, &mut events);
+            assert_eq!(state.frame_index(), 6);
+            assert_eq!(
+                events.last(),
+                Some(&Event::FrameEntered {
+                    position: 2,
+                    index: None
+                })
+            );
+            assert_eq!(animation.frame_at(frame_duration * 2).index(), None);
+        }
+    }
+
+    mod speed {
+        use super::*;
+
+        #[fixture]
+        fn animation(frame_rate: FrameRate) -> Animation {
+            Animation::from_indices(0..=3, frame_rate)
+        }
+
+        #[rstest]
+        fn default_speed_is_one() {
+            assert!((State::new().speed() - 1.0).abs() < f64::EPSILON);
+        }
+
+        #[rstest]
+        fn zero_speed_pauses(animation: Animation, frame_duration: Duration) {
+            let mut state = State::new();
+            state.set_speed(0.0);
+            state.update(&animation, frame_duration * 2);
+            assert_eq!(state.frame_index(), 0);
+        }
+
+        #[rstest]
+        fn faster_speed(animation: Animation, frame_duration: Duration) {
+            let mut state = State::new();
+            state.set_speed(2.0);
+            state.update(&animation, frame_duration);
+            assert_eq!(state.frame_index(), 2);
+        }
+
+        #[rstest]
+        fn slower_speed(animation: Animation, frame_duration: Duration) {
+            let mut state = State::new();
+            state.set_speed(0.5);
+            state.update(&animation, frame_duration);
+            assert_eq!(state.frame_index(), 0);
+            state.update(&animation, frame_duration);
+            assert_eq!(state.frame_index(), 1);
+        }
+
+        #[rstest]
+        fn reset_preserves_speed(animation: Animation, frame_duration: Duration) {
+            let mut state = State::new();
+            state.set_speed(2.0);
+            state.update(&animation, frame_duration);
+            state.reset();
+            assert!((state.speed() - 2.0).abs() < f64::EPSILON);
+            assert_eq!(state.elapsed_in_frame, Duration::ZERO);
+        }
+
+        #[rstest]
+        #[should_panic(expected = "Invalid speed")]
+        fn panics_for_invalid_speed(#[values(f64::NAN, f64::INFINITY)] speed: f64) {
+            State::new().set_speed(speed);
+        }
+
+        mod reverse {
+            use super::*;
+
+            fn reversed() -> State {
+                let mut state = State::new();
+                state.set_speed(-1.0);
+                state
+            }
+
+            #[rstest]
+            fn goes_to_previous_frame(animation: Animation, frame_duration: Duration) {
+                let mut state = State::new();
+                state.update(&animation, frame_duration * 2);
+                state.set_speed(-1.0);
+                state.update(&animation, Duration::from_millis(1));
+                assert_eq!(state.frame_index(), 1);
+                assert_eq!(
+                    state.elapsed_in_frame,
+                    frame_duration
+                        .checked_sub(Duration::from_millis(1))
+                        .unwrap()
+                );
+            }
+
+            #[rstest]
+            fn stays_on_frame_start(animation: Animation, frame_duration: Duration) {
+                let mut state = State::new();
+                state.update(&animation, frame_duration * 2);
+                state.set_speed(-1.0);
+                state.update(&animation, frame_duration);
+                assert_eq!(state.frame_index(), 1);
+                assert_eq!(state.elapsed_in_frame, Duration::ZERO);
+            }
+
+            #[rstest]
+            fn repeat_wraps_to_last_frame(animation: Animation, frame_duration: Duration) {
+                let mut state = reversed();
+                state.update(&animation, frame_duration);
+                assert_eq!(state.frame_index(), 3);
+                assert!(!state.is_ended());
+            }
+
+            #[rstest]
+            fn repeat_from_wraps_to_last_frame(frame_rate: FrameRate, frame_duration: Duration) {
+                let animation = Animation::from_indices(0..=3, frame_rate).repeat_from(2);
+                let mut state = State::new();
+                state.update(&animation, frame_duration * 2);
+                state.set_speed(-1.0);
+                state.update(&animation, frame_duration / 2);
+                assert_eq!(state.frame_index(), 3);
+            }
+
+            #[rstest]
+            fn ping_pong(frame_rate: FrameRate, frame_duration: Duration) {
+                let animation = Animation::from_indices(0..=2, frame_rate).ping_pong();
+                let mut state = reversed();
+                let mut indices = Vec::new();
+                for _ in 0..6 {
+                    state.update(&animation, frame_duration);
+                    indices.push(state.frame_index());
+                }
+                assert_eq!(indices, vec![1, 2, 1, 0, 1, 2]);
+            }
+
+            #[rstest]
+            fn ping_pong_rewinds_forward_play(frame_rate: FrameRate, frame_duration: Duration) {
+                let animation = Animation::from_indices(0..=2, frame_rate).ping_pong();
+                let mut state = State::new();
+                state.update(&animation, frame_duration * 3);
+                assert_eq!(state.frame_index(), 1);
+                state.set_speed(-1.0);
+                let mut indices = Vec::new();
+                for _ in 0..3 {
+                    state.update(&animation, frame_duration);
+                    indices.push(state.frame_index());
+                }
+                assert_eq!(indices, vec![2, 1, 0]);
+            }
+
+            #[rstest]
+            fn once_ends_on_first_frame(frame_rate: FrameRate, frame_duration: Duration) {
+                let animation = Animation::from_indices(0..=3, frame_rate).once();
+                let mut state = State::new();
+                state.update(&animation, frame_duration);
+                state.set_speed(-1.0);
+                state.update(&animation, frame_duration * 5);
+                assert_eq!(state.frame_index(), 0);
+                assert!(state.is_ended());
+            }
+
+            #[rstest]
+            fn once_resumes_backward_after_end(frame_rate: FrameRate, frame_duration: Duration) {
+                let animation = Animation::from_indices(0..=3, frame_rate).once();
+                let mut state = State::new();
+                state.update(&animation, frame_duration * 10);
+                assert!(state.is_ended());
+                state.set_speed(-1.0);
+                state.update(&animation, frame_duration / 2);
+                assert!(!state.is_ended());
+                assert_eq!(state.frame_index(), 3);
+                state.update(&animation, frame_duration);
+                assert_eq!(state.frame_index(), 2);
+            }
+
+            #[rstest]
+            fn once_resumes_forward_after_end(frame_rate: FrameRate, frame_duration: Duration) {
+                let animation = Animation::from_indices(0..=3, frame_rate).once();
+                let mut state = reversed();
+                state.update(&animation, frame_duration);
+                assert!(state.is_ended());
+                state.set_speed(1.0);
+                state.update(&animation, frame_duration);
+                assert!(!state.is_ended());
+                assert_eq!(state.frame_index(), 1);
+            }
+
+            #[rstest]
+            fn retraces_forward_play(
+                #[values(
+                    Animation::from_indices(0..=3, FrameRate::from_fps(1.0)).ping_pong(),
+                    Animation::from_indices(0..=1, FrameRate::from_fps(1.0)).ping_pong(),
+                    Animation::from_indices(0..=3, FrameRate::from_fps(1.0))
+                        .repeat_from_times(2, NonZeroU32::new(3).unwrap()),
+                    Animation::from_indices(0..=3, FrameRate::from_fps(1.0))
+                        .ping_pong_times(NonZeroU32::new(4).unwrap()),
+                    Animation::from_indices(0..=1, FrameRate::from_fps(1.0))
+                        .ping_pong_times(NonZeroU32::new(7).unwrap()),
+                    Animation::from_indices(0..=0, FrameRate::from_fps(1.0))
+                        .ping_pong_times(NonZeroU32::new(7).unwrap()),
+                    Animation::from_indices(0..=4, FrameRate::from_fps(1.0)).sustain(1..=2),
+                )]
+                animation: Animation,
+            ) {
+                // Stays before the end of the finite animations
+                let step = Duration::from_millis(300);
+                let mut state = State::new();
+                let mut forward = vec![state.frame_index()];
+                for _ in 0..20 {
+                    state.update(&animation, step);
+                    forward.push(state.frame_index());
+                }
+                state.set_speed(-1.0);
+                let mut backward = vec![state.frame_index()];
+                for _ in 0..20 {
+                    state.update(&animation, step);
+                    backward.push(state.frame_index());
+                }
+                backward.reverse();
+                assert_eq!(forward, backward);
+            }
+
+            #[rstest]
+            fn reports_events(frame_rate: FrameRate, frame_duration: Duration) {
+                let animation = Animation::from_indices(0..=1, frame_rate)
+                    .once()
+                    .with_marker_at(1, Duration::from_millis(500), "a");
+                let mut state = State::new();
+                state.update(&animation, frame_duration + Duration::from_millis(700));
+                state.set_speed(-1.0);
+                let mut events = Vec::new();
+                state.update_with_events(&animation, frame_duration * 2, &mut events);
+                assert_eq!(
+                    events,
+                    vec![
+                        Event::Marker { name: "a" },
+                        Event::FrameEntered {
+                            position: 0,
+                            index: Some(0)
+                        },
+                        Event::Ended,
+                    ]
+                );
+            }
+        }
+    }
+
+    mod seek {
+        use super::*;
+
+        #[fixture]
+        fn animation(frame_rate: FrameRate) -> Animation {
+            Animation::from_indices(0..=3, frame_rate)
+        }
+
+        #[rstest]
+        fn seek_to_elapsed_time(animation: Animation) {
+            let mut state = State::new();
+            state.seek(&animation, Duration::from_millis(2500));
+            assert_eq!(state.frame_index(), 2);
+            assert_eq!(state.elapsed_in_frame(), Duration::from_millis(500));
+            assert_eq!(state.elapsed(), Duration::from_millis(2500));
+        }
+
+        #[rstest]
+        fn seek_loops(animation: Animation) {
+            let mut state = State::new();
+            state.seek(&animation, Duration::from_millis(5500));
+            assert_eq!(state.frame_index(), 1);
+            assert_eq!(state.elapsed_in_frame(), Duration::from_millis(500));
+        }
+
+        #[rstest]
+        fn seek_ends_once_animation(animation: Animation) {
+            let animation = animation.once();
+            let mut state = State::new();
+            state.seek(&animation, Duration::from_secs(10));
+            assert_eq!(state.frame_index(), 3);
+            assert!(state.is_ended());
+            assert_eq!(state.elapsed(), Duration::from_secs(4));
+        }
+
+        #[rstest]
+        fn seek_overrides_current_state(animation: Animation, frame_duration: Duration) {
+            let animation = animation.once();
+            let mut state = State::new();
+            state.update(&animation, frame_duration * 10);
+            state.seek(&animation, Duration::ZERO);
+            assert_eq!(state.frame_index(), 0);
+            assert!(!state.is_ended());
+        }
+
+        #[rstest]
+        fn seek_does_not_report_first_frame(animation: Animation) {
+            let mut state = State::new();
+            state.seek(&animation, Duration::from_millis(2500));
+            let mut events = Vec::new();
+            state.update_with_events(&animation, Duration::ZERO, &mut events);
+            assert_eq!(events, vec![]);
+        }
+
+        #[rstest]
+        fn seek_frame(animation: Animation) {
+            let mut state = State::new();
+            state.seek_frame(&animation, 2);
+            assert_eq!(state.frame_index(), 2);
+            assert_eq!(state.elapsed_in_frame(), Duration::ZERO);
+            assert_eq!(state.elapsed(), Duration::from_secs(2));
+        }
+
+        #[rstest]
+        #[should_panic(expected = "Invalid frame position")]
+        fn seek_invalid_frame(animation: Animation) {
+            State::new().seek_frame(&animation, 4);
+        }
+
+        #[rstest]
+        #[case(0.0, 0, Duration::ZERO)]
+        #[case(0.5, 2, Duration::ZERO)]
+        #[case(0.6, 2, Duration::from_millis(400))]
+        #[case(1.0, 0, Duration::ZERO)]
+        fn seek_progress(
+            animation: Animation,
+            #[case] progress: f64,
+            #[case] expected_index: usize,
+            #[case] expected_elapsed: Duration,
+        ) {
+            let mut state = State::new();
+            state.seek_progress(&animation, progress);
+            assert_eq!(state.frame_index(), expected_index);
+            assert_eq!(state.elapsed_in_frame(), expected_elapsed);
+        }
+
+        #[rstest]
+        fn seek_full_progress_ends_once_animation(animation: Animation) {
+            let animation = animation.once();
+            let mut state = State::new();
+            state.seek_progress(&animation, 1.0);
+            assert_eq!(state.frame_index(), 3);
+            assert!(state.is_ended());
+        }
+
+        #[rstest]
+        #[should_panic(expected = "Invalid progress")]
+        fn seek_invalid_progress(
+            animation: Animation,
+            #[values(-0.1, 1.1, f64::NAN)] progress: f64,
+        ) {
+            State::new().seek_progress(&animation, progress);
+        }
+
+        #[rstest]
+        fn progress(animation: Animation) {
+            let mut state = State::new();
+            state.seek(&animation, Duration::from_secs(3));
+            assert!((state.progress(&animation) - 0.75).abs() < f64::EPSILON);
+        }
+
+        #[rstest]
+        fn progress_is_one_when_ended(animation: Animation, frame_duration: Duration) {
+            let animation = animation.once();
+            let mut state = State::new();
+            state.update(&animation, frame_duration * 10);
+            assert!((state.progress(&animation) - 1.0).abs() < f64::EPSILON);
+        }
+
+        #[rstest]
+        fn seek_progress_then_progress(animation: Animation) {
+            let mut state = State::new();
+            state.seek_progress(&animation, 0.3);
+            assert!((state.progress(&animation) - 0.3).abs() < 1e-9);
+        }
+
+        #[rstest]
+        fn remaining(animation: Animation) {
+            let animation = animation.once();
+            let mut state = State::new();
+            state.seek(&animation, Duration::from_millis(2500));
+            assert_eq!(
+                state.remaining(&animation),
+                Some(Duration::from_millis(1500))
+            );
+        }
+
+        #[rstest]
+        fn remaining_is_zero_when_ended(animation: Animation, frame_duration: Duration) {
+            let animation = animation.once();
+            let mut state = State::new();
+            state.update(&animation, frame_duration * 10);
+            assert_eq!(state.remaining(&animation), Some(Duration::ZERO));
+        }
+
+        #[rstest]
+        fn no_remaining_time_for_infinite_animations(
+            #[values(
+                Animation::from_indices(0..=3, FrameRate::from_fps(1.0)).repeat(),
+                Animation::from_indices(0..=3, FrameRate::from_fps(1.0)).ping_pong()
+            )]
+            animation: Animation,
+        ) {
+            assert_eq!(State::new().remaining(&animation), None);
+        }
+
+        #[rstest]
+        fn elapsed_accumulates(animation: Animation, frame_duration: Duration) {
+            let mut state = State::new();
+            state.update(&animation, frame_duration * 6);
+            state.update(&animation, frame_duration / 2);
+            assert_eq!(state.elapsed(), frame_duration * 6 + frame_duration / 2);
+        }
+
+        #[rstest]
+        fn elapsed_decreases_when_playing_backward(animation: Animation, frame_duration: Duration) {
+            let mut state = State::new();
+            state.update(&animation, frame_duration * 3);
+            state.set_speed(-1.0);
+            state.update(&animation, frame_duration);
+            assert_eq!(state.elapsed(), frame_duration * 2);
+        }
+
+        #[rstest]
+        fn elapsed_stops_at_end(animation: Animation, frame_duration: Duration) {
+            let animation = animation.once();
+            let mut state = State::new();
+            state.update(&animation, frame_duration * 10);
+            assert_eq!(state.elapsed(), frame_duration * 4);
+        }
+    }
+}
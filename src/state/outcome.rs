@@ -0,0 +1,208 @@
+use std::time::Duration;
+
+use crate::{Animation, Event, SpriteIndex, State};
+
+/// What changed during an update of a [`State`], with the value of the current frame
+///
+/// It is returned by [`State::update`] and the other update methods,
+/// so that an engine only writes the sprite index when it [changed](Self::index_changed),
+/// and reacts to the [end](Self::just_ended) of an animation without remembering the previous [`State::is_ended`].
+///
+/// # Example
+///
+/// ```
+/// # use std::time::Duration;
+/// use benimator::*;
+///
+/// let animation = Animation::from_indices(0..=3, FrameRate::from_fps(10.0)).once();
+/// let mut state = State::new();
+///
+/// let outcome = state.update(&animation, Duration::from_millis(50));
+/// assert!(outcome.index_changed());
+///
+/// let outcome = state.update(&animation, Duration::from_millis(20));
+/// assert!(!outcome.index_changed());
+///
+/// let outcome = state.update(&animation, Duration::from_secs(1));
+/// assert_eq!(outcome.frames_advanced(), 3);
+/// assert_eq!(*outcome.value(), 3);
+/// assert!(outcome.just_ended());
+///
+/// let outcome = state.update(&animation, Duration::from_secs(1));
+/// assert!(!outcome.just_ended());
+/// ```
+#[derive(Debug, Eq, PartialEq)]
+pub struct UpdateOutcome<'a, T = usize> {
+    value: &'a T,
+    index_changed: bool,
+    frames_advanced: usize,
+    loops_completed: usize,
+    just_ended: bool,
+}
+
+impl<T> Clone for UpdateOutcome<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for UpdateOutcome<'_, T> {}
+
+impl<'a, T> UpdateOutcome<'a, T> {
+    /// Returns the value of the current frame
+    ///
+    /// See [`State::frame_value`]
+    #[must_use]
+    pub fn value(&self) -> &'a T {
+        self.value
+    }
+
+    /// Returns true if the sprite index changed during the update
+    ///
+    /// That is always the case for the first update of a state, since its first frame is entered.
+    /// It is false if the frame changed to another frame with the same sprite index,
+    /// or if the animation went through a full cycle back to the same frame.
+    #[must_use]
+    pub fn index_changed(&self) -> bool {
+        self.index_changed
+    }
+
+    /// Returns the number of frames entered during the update, not counting the first frame of a state never updated
+    ///
+    /// That includes the frames that are skipped over when the time elapsed is longer than their duration.
+    #[must_use]
+    pub fn frames_advanced(&self) -> usize {
+        self.frames_advanced
+    }
+
+    /// Returns the number of times the animation went back to the frame it repeats from,
+    /// or changed direction for a ping-pong animation, during the update
+    #[must_use]
+    pub fn loops_completed(&self) -> usize {
+        self.loops_completed
+    }
+
+    /// Returns true if the animation reached its end during this update
+    ///
+    /// It is false for the following updates, even though the state [is ended](State::is_ended).
+    #[must_use]
+    pub fn just_ended(&self) -> bool {
+        self.just_ended
+    }
+}
+
+impl State {
+    /// Move the play-head by `delta`, and returns what changed, along with the leftover time after the end
+    pub(super) fn advance_with_outcome<'a, T: SpriteIndex>(
+        &mut self,
+        animation: &'a Animation<T>,
+        delta: Duration,
+        on_event: &mut impl FnMut(Event<'a>),
+    ) -> (UpdateOutcome<'a, T>, Duration) {
+        let previous = (self.is_started, self.sprite_frame_index);
+        let (mut frames_entered, mut loops_completed, mut just_ended) = (0, 0, false);
+        let leftover = self.advance(animation, delta, &mut |event| {
+            match event {
+                Event::FrameEntered { .. } => frames_entered += 1,
+                Event::Looped | Event::DirectionChanged => loops_completed += 1,
+                Event::Ended => just_ended = true,
+                Event::Marker { .. } => (),
+            }
+            on_event(event);
+        });
+        let frames_advanced = if previous.0 {
+            frames_entered
+        } else {
+            frames_entered - 1
+        };
+        let outcome = UpdateOutcome {
+            value: self.frame_value(animation),
+            index_changed: previous != (true, self.sprite_frame_index),
+            frames_advanced,
+            loops_completed,
+            just_ended,
+        };
+        (outcome, leftover)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FrameRate;
+
+    fn animation() -> Animation {
+        Animation::from_indices(0..=3, FrameRate::from_fps(10.0))
+    }
+
+    #[test]
+    fn first_update_changes_the_index() {
+        let animation = animation();
+        let mut state = State::new();
+        let outcome = state.update(&animation, Duration::ZERO);
+        assert!(outcome.index_changed());
+        assert_eq!(outcome.frames_advanced(), 0);
+        assert_eq!(*outcome.value(), 0);
+    }
+
+    #[rstest]
+    #[case(animation(), Duration::from_millis(450), 4, 1)]
+    #[case(animation(), Duration::from_millis(850), 8, 2)]
+    #[case(animation().ping_pong(), Duration::from_millis(850), 8, 2)]
+    #[case(animation().repeat_from(2), Duration::from_millis(850), 8, 3)]
+    fn counts_frames_and_loops(
+        #[case] animation: Animation,
+        #[case] delta: Duration,
+        #[case] expected_frames: usize,
+        #[case] expected_loops: usize,
+    ) {
+        let mut state = State::new();
+        state.update(&animation, Duration::ZERO);
+        let outcome = state.update(&animation, delta);
+        assert_eq!(outcome.frames_advanced(), expected_frames);
+        assert_eq!(outcome.loops_completed(), expected_loops);
+        assert!(!outcome.just_ended());
+    }
+
+    #[test]
+    fn full_cycle_keeps_the_index() {
+        let animation = animation();
+        let mut state = State::new();
+        state.update(&animation, Duration::from_millis(50));
+        let outcome = state.update(&animation, Duration::from_millis(400));
+        assert_eq!(outcome.frames_advanced(), 4);
+        assert!(!outcome.index_changed());
+    }
+
+    #[test]
+    fn reports_the_end_once() {
+        let animation = animation().once();
+        let mut state = State::new();
+        assert!(state
+            .update(&animation, Duration::from_secs(1))
+            .just_ended());
+        assert!(!state
+            .update(&animation, Duration::from_secs(1))
+            .just_ended());
+        state.set_speed(-1.0);
+        let outcome = state.update(&animation, Duration::from_secs(1));
+        assert!(outcome.just_ended());
+        assert!(outcome.index_changed());
+    }
+
+    #[test]
+    fn events_are_still_reported() {
+        let animation = animation();
+        let mut state = State::new();
+        let mut events = Vec::new();
+        let outcome = state.update_with_events(&animation, Duration::from_millis(150), &mut events);
+        assert_eq!(outcome.frames_advanced(), 1);
+        assert_eq!(
+            events
+                .iter()
+                .filter(|event| matches!(event, Event::FrameEntered { .. }))
+                .count(),
+            2
+        );
+    }
+}
@@ -0,0 +1,278 @@
+use std::{collections::VecDeque, time::Duration};
+
+use crate::{AnimationSet, Event, MissingAnimation, SpriteIndex, State};
+
+/// Play-back state of a sequence of animations of an [`AnimationSet`]
+///
+/// When the current animation ends (see [`State::is_ended`]), the queue continues with the next queued animation,
+/// or with the [next](crate::Animation::next) animation declared by the current one if nothing is queued.
+/// The time left over after the end of an animation is played in the next one,
+/// so that the sequence is not delayed by the frame-rate of the updates.
+///
+/// Animations that never end are played until another animation is [played](Self::play).
+///
+/// # Example
+///
+/// ```
+/// # use std::time::Duration;
+/// use benimator::*;
+///
+/// let set = AnimationSet::new()
+///     .with_animation("attack", Animation::from_indices(0..=2, FrameRate::from_fps(10.0)).once())
+///     .with_animation("recover", Animation::from_indices(3..=4, FrameRate::from_fps(10.0)).once())
+///     .with_animation("idle", Animation::from_indices(5..=6, FrameRate::from_fps(10.0)));
+///
+/// let mut queue = AnimationQueue::new("attack");
+/// queue.enqueue("recover");
+/// queue.enqueue("idle");
+///
+/// queue.update(&set, Duration::from_millis(350)).unwrap();
+/// assert_eq!(queue.current(), "recover");
+/// assert_eq!(queue.frame_index(), 3);
+///
+/// queue.update(&set, Duration::from_millis(200)).unwrap();
+/// assert_eq!(queue.current(), "idle");
+/// assert_eq!(queue.frame_index(), 5);
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct AnimationQueue {
+    current: String,
+    queued: VecDeque<String>,
+    state: State,
+}
+
+impl AnimationQueue {
+    /// Create a queue which starts by playing the named animation
+    #[must_use]
+    pub fn new(animation: impl Into<String>) -> Self {
+        Self {
+            current: animation.into(),
+            queued: VecDeque::new(),
+            state: State::new(),
+        }
+    }
+
+    /// Returns the name of the animation being played
+    #[must_use]
+    pub fn current(&self) -> &str {
+        &self.current
+    }
+
+    /// Iterate over the names of the queued animations, in the order they will be played
+    pub fn queued(&self) -> impl Iterator<Item = &str> {
+        self.queued.iter().map(String::as_str)
+    }
+
+    /// Add the named animation at the end of the queue
+    pub fn enqueue(&mut self, animation: impl Into<String>) {
+        self.queued.push_back(animation.into());
+    }
+
+    /// Remove all the queued animations
+    ///
+    /// The current animation keeps playing, and is followed by its [next](crate::Animation::next) animation, if any.
+    pub fn clear(&mut self) {
+        self.queued.clear();
+    }
+
+    /// Play the named animation from its first frame, and remove all the queued animations
+    ///
+    /// The [speed](State::speed) of the state is preserved.
+    pub fn play(&mut self, animation: impl Into<String>) {
+        self.current = animation.into();
+        self.queued.clear();
+        self.state.reset();
+    }
+
+    /// Returns the state of the animation being played
+    #[must_use]
+    pub fn state(&self) -> &State {
+        &self.state
+    }
+
+    /// Returns a mutable reference to the state of the animation being played
+    ///
+    /// This may be used to change the [speed](State::speed), which is preserved when the next animation starts.
+    #[must_use]
+    pub fn state_mut(&mut self) -> &mut State {
+        &mut self.state
+    }
+
+    /// Returns the current frame index
+    #[must_use]
+    pub fn frame_index(&self) -> usize {
+        self.state.frame_index()
+    }
+
+    /// Update the current animation, continue with the next ones if it ends,
+    /// and returns the value of the current frame
+    ///
+    /// The animations are only chained when playing forward:
+    /// an animation played backward stays on its first frame when it ends.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MissingAnimation`] if the current or next animation is not in the set.
+    /// The queue is then left on the last animation found.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an animation played has no frame
+    pub fn update<'a, T: SpriteIndex>(
+        &mut self,
+        set: &'a AnimationSet<T>,
+        delta: Duration,
+    ) -> Result<&'a T, MissingAnimation> {
+        self.advance(set, delta, &mut |_| ())
+    }
+
+    /// Update the queue, and report what happened to the animations played
+    ///
+    /// An [`Event::Ended`] is reported for each animation that ends,
+    /// followed by the [`Event::FrameEntered`] of the first frame of the next one.
+    ///
+    /// See [`update`](Self::update) and [`State::update_with_events`]
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MissingAnimation`] if the current or next animation is not in the set
+    ///
+    /// # Panics
+    ///
+    /// Panics if an animation played has no frame
+    pub fn update_with_events<'a, T: SpriteIndex>(
+        &mut self,
+        set: &'a AnimationSet<T>,
+        delta: Duration,
+        events: &mut impl Extend<Event<'a>>,
+    ) -> Result<(), MissingAnimation> {
+        self.advance(set, delta, &mut |event| events.extend(Some(event)))?;
+        Ok(())
+    }
+
+    fn advance<'a, T: SpriteIndex>(
+        &mut self,
+        set: &'a AnimationSet<T>,
+        delta: Duration,
+        on_event: &mut impl FnMut(Event<'a>),
+    ) -> Result<&'a T, MissingAnimation> {
+        let mut animation = set.get(&self.current)?;
+        let mut leftover = self.state.update_with_leftover(animation, delta, on_event);
+        while self.state.is_ended() && !self.state.speed().is_sign_negative() {
+            let Some(next) = self.queued.front().map(String::as_str).or(animation.next()) else {
+                break;
+            };
+            animation = set.get(next)?;
+            self.current = next.to_owned();
+            self.queued.pop_front();
+            self.state.reset();
+            leftover = self
+                .state
+                .update_with_leftover(animation, leftover, on_event);
+        }
+        Ok(self.state.frame_value(animation))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Animation, FrameRate};
+
+    fn set() -> AnimationSet {
+        let frame_rate = FrameRate::from_frame_duration(Duration::from_millis(100));
+        AnimationSet::new()
+            .with_animation("attack", Animation::from_indices(0..=2, frame_rate).once())
+            .with_animation(
+                "recover",
+                Animation::from_indices(3..=4, frame_rate)
+                    .once()
+                    .with_next("idle"),
+            )
+            .with_animation("idle", Animation::from_indices(5..=6, frame_rate))
+    }
+
+    #[test]
+    fn carries_over_leftover_time() {
+        let mut queue = AnimationQueue::new("attack");
+        queue.enqueue("recover");
+        queue.update(&set(), Duration::from_millis(340)).unwrap();
+        assert_eq!(queue.current(), "recover");
+        assert_eq!(queue.state().elapsed_in_frame(), Duration::from_millis(40));
+        assert_eq!(queue.queued().count(), 0);
+    }
+
+    #[test]
+    fn follows_next_animation() {
+        let mut queue = AnimationQueue::new("recover");
+        let set = set();
+        let value = queue.update(&set, Duration::from_millis(250)).unwrap();
+        assert_eq!(queue.current(), "idle");
+        assert_eq!(*value, 5);
+        assert_eq!(queue.state().elapsed_in_frame(), Duration::from_millis(50));
+    }
+
+    #[test]
+    fn chains_several_animations_in_one_update() {
+        let mut queue = AnimationQueue::new("attack");
+        queue.enqueue("recover");
+        let set = set();
+        let mut events = Vec::new();
+        queue
+            .update_with_events(&set, Duration::from_millis(610), &mut events)
+            .unwrap();
+        assert_eq!(queue.current(), "idle");
+        assert_eq!(queue.frame_index(), 6);
+        assert_eq!(
+            events
+                .iter()
+                .filter(|event| matches!(event, Event::Ended))
+                .count(),
+            2
+        );
+    }
+
+    #[test]
+    fn stays_ended_without_next() {
+        let mut queue = AnimationQueue::new("attack");
+        queue.update(&set(), Duration::from_secs(1)).unwrap();
+        assert_eq!(queue.current(), "attack");
+        assert!(queue.state().is_ended());
+        queue.enqueue("idle");
+        queue.update(&set(), Duration::from_millis(150)).unwrap();
+        assert_eq!(queue.current(), "idle");
+        assert_eq!(queue.frame_index(), 6);
+    }
+
+    #[test]
+    fn waits_for_the_end_of_repeating_animation() {
+        let mut queue = AnimationQueue::new("idle");
+        queue.enqueue("attack");
+        queue.update(&set(), Duration::from_secs(10)).unwrap();
+        assert_eq!(queue.current(), "idle");
+        assert_eq!(queue.queued().collect::<Vec<_>>(), vec!["attack"]);
+    }
+
+    #[test]
+    fn play_interrupts_the_queue() {
+        let mut queue = AnimationQueue::new("idle");
+        queue.state_mut().set_speed(2.0);
+        queue.enqueue("attack");
+        queue.update(&set(), Duration::from_millis(100)).unwrap();
+        queue.play("recover");
+        assert_eq!(queue.queued().count(), 0);
+        assert!((queue.state().speed() - 2.0).abs() < f64::EPSILON);
+        queue.update(&set(), Duration::from_millis(60)).unwrap();
+        assert_eq!(queue.frame_index(), 4);
+    }
+
+    #[test]
+    fn missing_animation() {
+        let mut queue = AnimationQueue::new("attack");
+        queue.enqueue("jump");
+        let error = queue.update(&set(), Duration::from_secs(1)).unwrap_err();
+        assert_eq!(error.name(), "jump");
+        assert_eq!(queue.current(), "attack");
+        assert_eq!(queue.queued().collect::<Vec<_>>(), vec!["jump"]);
+    }
+}
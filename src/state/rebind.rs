@@ -0,0 +1,215 @@
+use std::time::Duration;
+
+use crate::{animation::Mode, Animation, SpriteIndex, State};
+
+/// How a [`State`] is moved to an animation that replaced the one it was playing
+///
+/// See [`State::rebind`]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub enum RebindPolicy {
+    /// Keep the current frame position, or go to the last frame if the new animation has fewer frames
+    Clamp,
+    /// Keep the current frame position, wrapped around the number of frames of the new animation
+    Wrap,
+    /// Restart from the first frame, like if the animation was freshly spawned
+    Restart,
+    /// Keep the same [progress](State::progress) as in the previous animation
+    ///
+    /// If the previous animation is unknown, because the state was never updated or was deserialized,
+    /// it is the same as [`RebindPolicy::Clamp`].
+    KeepProgress,
+}
+
+/// Properties of the animation a state was last updated with
+///
+/// They tell whether the state is updated with a different animation,
+/// and where the state was in that animation, even after it is dropped.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub(super) struct Binding {
+    frame_count: usize,
+    duration: Duration,
+    mode: Mode,
+    /// Time at which the current frame starts
+    frame_start: Duration,
+}
+
+impl Binding {
+    pub(super) fn new<T>(animation: &Animation<T>, position: usize) -> Self {
+        Self::of(animation).at(animation.frame_start(position))
+    }
+
+    /// Returns the binding of a state at the first frame of `animation`
+    ///
+    /// It may be computed once, and moved [`at`](Self::at) the frame of each state played with the same animation.
+    pub(super) fn of<T>(animation: &Animation<T>) -> Self {
+        Self {
+            frame_count: animation.frames.len(),
+            duration: animation.duration(),
+            mode: animation.mode,
+            frame_start: Duration::ZERO,
+        }
+    }
+
+    /// Returns the same binding, for the frame starting at `frame_start`
+    pub(super) fn at(self, frame_start: Duration) -> Self {
+        Self {
+            frame_start,
+            ..self
+        }
+    }
+
+    /// Returns true if the animation may be the one the state was last updated with
+    ///
+    /// Animations with the same number of frames, duration and mode are not distinguished.
+    pub(super) fn matches<T>(&self, animation: &Animation<T>) -> bool {
+        self.same_animation(&Self::of(animation))
+    }
+
+    /// Returns true if both bindings may be of the same animation, whatever their frame
+    pub(super) fn same_animation(&self, other: &Self) -> bool {
+        self.frame_count == other.frame_count
+            && self.duration == other.duration
+            && self.mode == other.mode
+    }
+}
+
+impl State {
+    /// Returns true if the state may have been last updated with `animation`, or if it is unknown
+    ///
+    /// See [`Binding::matches`]
+    pub(crate) fn is_bound_to<T>(&self, animation: &Animation<T>) -> bool {
+        self.binding
+            .map_or(true, |binding| binding.matches(animation))
+    }
+
+    /// Move the state to `animation`, which replaces the animation it was playing
+    ///
+    /// This is needed when an animation is modified while being played (for instance when the asset is hot-reloaded),
+    /// or when another animation is played without [resetting](Self::reset) the state.
+    /// Otherwise, the current frame position may not exist in the new animation.
+    ///
+    /// A state updated with an animation which number of frames, duration or mode differs from the one of the previous
+    /// update is rebound with [`RebindPolicy::Clamp`], unless it is rebound or [reset](Self::reset) first.
+    ///
+    /// No [`Event`](crate::Event) is reported.
+    /// The state is set to be moving forward, unless the new animation is a ping-pong animation.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use std::time::Duration;
+    /// use benimator::*;
+    ///
+    /// let mut animation = Animation::from_indices(0..=5, FrameRate::from_fps(10.0));
+    /// let mut state = State::new();
+    /// state.update(&animation, Duration::from_millis(450));
+    ///
+    /// // The animation is modified, like after a hot-reload
+    /// animation = Animation::from_indices(0..=2, FrameRate::from_fps(10.0));
+    /// state.rebind(&animation, RebindPolicy::Clamp);
+    ///
+    /// assert_eq!(state.frame_index(), 2);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the animation has no frame
+    pub fn rebind<T: SpriteIndex>(&mut self, animation: &Animation<T>, policy: RebindPolicy) {
+        assert!(animation.has_frames(), "The animation has no frame");
+        let frame_count = animation.frames.len();
+        let position = match policy {
+            RebindPolicy::Restart => return self.reset(),
+            RebindPolicy::KeepProgress => {
+                if let Some(binding) = self.binding.filter(|b| !b.duration.is_zero()) {
+                    let current = binding.frame_start + self.elapsed_in_frame;
+                    let progress = current.as_secs_f64() / binding.duration.as_secs_f64();
+                    return self.seek_progress(animation, progress.min(1.0));
+                }
+                self.animation_frame_index.min(frame_count - 1)
+            }
+            RebindPolicy::Clamp => self.animation_frame_index.min(frame_count - 1),
+            RebindPolicy::Wrap => self.animation_frame_index % frame_count,
+        };
+        let going_backward = self.going_backward
+            && matches!(animation.mode, Mode::PingPong | Mode::PingPongTimes(_));
+        let elapsed_in_frame = self
+            .elapsed_in_frame
+            .min(animation.frames[position].duration);
+        self.place(animation, position, elapsed_in_frame);
+        self.going_backward = going_backward;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FrameRate;
+
+    fn long() -> Animation {
+        Animation::from_indices(10..=15, FrameRate::from_fps(10.0))
+    }
+
+    fn short() -> Animation {
+        Animation::from_indices(0..=2, FrameRate::from_fps(5.0))
+    }
+
+    #[rstest]
+    #[case(RebindPolicy::Clamp, 2, Duration::ZERO)]
+    #[case(RebindPolicy::Wrap, 0, Duration::ZERO)]
+    #[case(RebindPolicy::Restart, 0, Duration::ZERO)]
+    #[case(RebindPolicy::KeepProgress, 1, Duration::from_millis(100))]
+    fn policies(
+        #[case] policy: RebindPolicy,
+        #[case] expected_index: usize,
+        #[case] expected_elapsed_in_frame: Duration,
+    ) {
+        let mut state = State::new();
+        state.update(&long(), Duration::from_millis(300));
+        state.rebind(&short(), policy);
+        assert_eq!(state.frame_index(), expected_index);
+        assert_eq!(state.elapsed_in_frame(), expected_elapsed_in_frame);
+        state.update(&short(), Duration::ZERO);
+    }
+
+    #[test]
+    fn keep_progress_without_previous_animation() {
+        let mut state = State::new();
+        state.rebind(&short(), RebindPolicy::KeepProgress);
+        assert_eq!(state.frame_index(), 0);
+    }
+
+    #[test]
+    fn direction_is_kept_for_ping_pong_only() {
+        let mut state = State::new();
+        let ping_pong = long().ping_pong();
+        state.update(&ping_pong, Duration::from_millis(650));
+        state.rebind(&ping_pong, RebindPolicy::Clamp);
+        state.update(&ping_pong, Duration::from_millis(100));
+        assert_eq!(state.frame_index(), 13);
+
+        state.rebind(&long(), RebindPolicy::Clamp);
+        state.update(&long(), Duration::from_millis(100));
+        assert_eq!(state.frame_index(), 14);
+    }
+
+    #[test]
+    fn update_with_different_animation_clamps() {
+        let mut state = State::new();
+        state.update(&long(), Duration::from_millis(650));
+        let mut rebound = state.clone();
+        rebound.rebind(&short(), RebindPolicy::Clamp);
+        rebound.update(&short(), Duration::from_millis(100));
+        state.update(&short(), Duration::from_millis(100));
+        assert_eq!(state, rebound);
+    }
+
+    #[test]
+    fn reset_allows_any_animation() {
+        let mut state = State::new();
+        state.update(&long(), Duration::from_millis(100));
+        state.reset();
+        state.update(&short(), Duration::from_millis(100));
+        assert_eq!(state.frame_index(), 0);
+    }
+}
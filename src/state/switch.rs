@@ -0,0 +1,163 @@
+use std::time::Duration;
+
+use crate::{Animation, SpriteIndex, State};
+
+impl State {
+    /// Switch from the animation `from` to the animation `to`, at the same progress
+    ///
+    /// The progress is the one given by [`progress`](Self::progress),
+    /// so that a state in the middle of `from` is placed in the middle of `to`,
+    /// whatever the number and durations of the frames of both animations.
+    /// It keeps in phase two animations of the same motion, like a walk and a run cycle.
+    ///
+    /// No [`Event`](crate::Event) is reported, and the state is set to be moving forward.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use std::time::Duration;
+    /// use benimator::*;
+    ///
+    /// let walk = Animation::from_indices(0..=3, FrameRate::from_fps(10.0));
+    /// let run = Animation::from_indices(4..=9, FrameRate::from_fps(20.0));
+    /// let mut state = State::new();
+    /// state.update(&walk, Duration::from_millis(200));
+    ///
+    /// state.switch_keeping_progress(&walk, &run);
+    /// assert_eq!(state.frame_index(), 7);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if either animation has no frame
+    pub fn switch_keeping_progress<T, U: SpriteIndex>(
+        &mut self,
+        from: &Animation<T>,
+        to: &Animation<U>,
+    ) {
+        self.seek_progress(to, self.progress(from));
+    }
+
+    /// Switch from the animation `from` to the animation `to`, aligning the named markers of both animations
+    ///
+    /// The last marker reached in `from` is looked up by name in `to`,
+    /// and the state is placed at the same fraction of the time between that marker and the next one.
+    /// For instance, with `left_foot` and `right_foot` markers (see [`Animation::with_marker`]) in both a walk and
+    /// a run animation, the feet stay in phase even if the steps are not evenly spaced.
+    ///
+    /// Markers that are never reached, because their offset is beyond the end of their frame, are ignored.
+    /// If `from` has no marker, or if `to` has no marker with the same name,
+    /// this is the same as [`switch_keeping_progress`](Self::switch_keeping_progress).
+    ///
+    /// No [`Event`](crate::Event) is reported, and the state is set to be moving forward.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either animation has no frame
+    pub fn switch_by_markers<T, U: SpriteIndex>(&mut self, from: &Animation<T>, to: &Animation<U>) {
+        let from_markers: Vec<(Duration, &str)> = from.marker_times().collect();
+        let to_markers: Vec<(Duration, &str)> = to.marker_times().collect();
+        let time =
+            (from.frame_start(self.position(from)) + self.elapsed_in_frame).min(from.duration());
+        let (position, time) = match from_markers.iter().rposition(|&(start, _)| start <= time) {
+            Some(position) => (position, time),
+            // Before the first marker, the last one reached is the last marker of the previous cycle
+            None if !from_markers.is_empty() => (from_markers.len() - 1, time + from.duration()),
+            None => return self.switch_keeping_progress(from, to),
+        };
+        let Some(to_position) = to_markers
+            .iter()
+            .position(|&(_, name)| name == from_markers[position].1)
+        else {
+            return self.switch_keeping_progress(from, to);
+        };
+        let (start, end) = span(&from_markers, position, from.duration());
+        let phase =
+            time.saturating_sub(start).as_secs_f64() / end.saturating_sub(start).as_secs_f64();
+        let (start, end) = span(&to_markers, to_position, to.duration());
+        let mut target = start + end.saturating_sub(start).mul_f64(phase);
+        if target >= to.duration() {
+            target -= to.duration();
+        }
+        self.seek_time(to, target);
+    }
+}
+
+/// Returns the time of the marker at `position`, and the time of the next marker,
+/// which is the first one of the next cycle after the last marker
+fn span(markers: &[(Duration, &str)], position: usize, duration: Duration) -> (Duration, Duration) {
+    let end = match markers.get(position + 1) {
+        Some(&(end, _)) => end,
+        None => markers[0].0 + duration,
+    };
+    (markers[position].0, end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FrameRate;
+
+    /// Steps at the beginning of frames 0 and 2
+    fn walk() -> Animation {
+        Animation::from_indices(0..=3, FrameRate::from_fps(10.0))
+            .with_marker(0, "left")
+            .with_marker(2, "right")
+    }
+
+    /// Steps at the beginning of frames 0 and 4, which are not evenly spaced
+    fn run() -> Animation {
+        Animation::from_indices(4..=9, FrameRate::from_fps(20.0))
+            .with_marker(0, "left")
+            .with_marker(4, "right")
+    }
+
+    #[rstest]
+    #[case(Duration::ZERO, 4)]
+    #[case(Duration::from_millis(200), 7)]
+    #[case(Duration::from_millis(350), 9)]
+    fn keeping_progress(#[case] elapsed: Duration, #[case] expected_index: usize) {
+        let mut state = State::new();
+        state.update(&walk(), elapsed);
+        state.switch_keeping_progress(&walk(), &run());
+        assert_eq!(state.frame_index(), expected_index);
+    }
+
+    #[rstest]
+    #[case(Duration::ZERO, 4, Duration::ZERO)]
+    #[case(Duration::from_millis(100), 6, Duration::ZERO)]
+    #[case(Duration::from_millis(150), 7, Duration::ZERO)]
+    #[case(Duration::from_millis(200), 8, Duration::ZERO)]
+    #[case(Duration::from_millis(300), 9, Duration::ZERO)]
+    fn by_markers(
+        #[case] elapsed: Duration,
+        #[case] expected_index: usize,
+        #[case] expected_elapsed_in_frame: Duration,
+    ) {
+        let mut state = State::new();
+        state.update(&walk(), elapsed);
+        state.switch_by_markers(&walk(), &run());
+        assert_eq!(state.frame_index(), expected_index);
+        assert_eq!(state.elapsed_in_frame(), expected_elapsed_in_frame);
+    }
+
+    #[test]
+    fn by_markers_before_the_first_marker() {
+        let walk = Animation::from_indices(0..=3, FrameRate::from_fps(10.0)).with_marker(2, "step");
+        let run = Animation::from_indices(4..=7, FrameRate::from_fps(10.0)).with_marker(1, "step");
+        let mut state = State::new();
+        state.update(&walk, Duration::ZERO);
+        state.switch_by_markers(&walk, &run);
+        assert_eq!(state.frame_index(), 7);
+    }
+
+    #[test]
+    fn by_markers_without_matching_marker() {
+        let other =
+            Animation::from_indices(4..=9, FrameRate::from_fps(20.0)).with_marker(1, "jump");
+        let mut state = State::new();
+        state.update(&walk(), Duration::from_millis(200));
+        state.switch_by_markers(&walk(), &other);
+        assert_eq!(state.frame_index(), 7);
+    }
+}